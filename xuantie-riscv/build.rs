@@ -0,0 +1,21 @@
+//! Emits a `xuantie_<core>` cfg for each XuanTie core whose APIs should be available.
+//!
+//! Every core is available when no core feature is selected; otherwise only the selected
+//! cores are.
+use std::env;
+
+const CORES: [&str; 8] = [
+    "c906", "c907", "c908", "c910", "c920", "e902", "e906", "e907",
+];
+
+fn main() {
+    let selected =
+        |core: &str| env::var_os(format!("CARGO_FEATURE_{}", core.to_uppercase())).is_some();
+    let any_selected = CORES.iter().any(|core| selected(core));
+    for core in CORES {
+        println!("cargo::rustc-check-cfg=cfg(xuantie_{core})");
+        if !any_selected || selected(core) {
+            println!("cargo::rustc-cfg=xuantie_{core}");
+        }
+    }
+}
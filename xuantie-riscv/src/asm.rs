@@ -14,6 +14,10 @@ mod xtheadsync;
 pub use xtheadsync::*;
 mod xtheadint;
 pub use xtheadint::*;
+mod zicbom;
+pub use zicbom::*;
+mod zifencei;
+pub use zifencei::*;
 
 // Available on some T-Head embedded cores, but not listed in XThead specification.
 pub mod dsp0p9;
@@ -16,6 +16,15 @@ use core::arch::asm;
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907, C908, E907 and E906 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
 #[inline]
 pub unsafe fn dcache_call() {
     // th.dcache.call
@@ -38,6 +47,15 @@ pub unsafe fn dcache_call() {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907, C908, E907 and E906 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
 #[inline]
 pub unsafe fn dcache_iall() {
     // th.dcache.iall
@@ -60,6 +78,15 @@ pub unsafe fn dcache_iall() {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907, C908, E907 and E906 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
 #[inline]
 pub unsafe fn dcache_ciall() {
     // th.dcache.ciall
@@ -82,6 +109,16 @@ pub unsafe fn dcache_ciall() {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907, C908, E907, E906 and E902 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906,
+    xuantie_e902
+))]
 #[inline]
 pub unsafe fn icache_iall() {
     // th.icache.iall
@@ -105,6 +142,7 @@ pub unsafe fn icache_iall() {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907 and C908 cores.
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908))]
 #[inline]
 pub unsafe fn icache_ialls() {
     // th.icache.ialls
@@ -127,6 +165,7 @@ pub unsafe fn icache_ialls() {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C910 core.
+#[cfg(xuantie_c910)]
 #[inline]
 pub unsafe fn l2cache_call() {
     // th.l2cache.call
@@ -149,6 +188,7 @@ pub unsafe fn l2cache_call() {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C910 and C906 cores.
+#[cfg(any(xuantie_c910, xuantie_c906))]
 #[inline]
 pub unsafe fn l2cache_iall() {
     // th.l2cache.iall
@@ -171,6 +211,7 @@ pub unsafe fn l2cache_iall() {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C910 core.
+#[cfg(xuantie_c910)]
 #[inline]
 pub unsafe fn l2cache_ciall() {
     // th.l2cache.ciall
@@ -221,6 +262,15 @@ pub unsafe fn l2cache_ciall() {
 /// The E906 core has a 2-way set-associative D-cache. Input variable `rs1[31]` represents number of way,
 /// while `rs1[w:6]` represents number of set. When D-cache is configured 32 Kibibytes, `w` equals 13;
 /// when configured 16 Kibibytes, `w` equals 12, and so on.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
 #[inline]
 pub unsafe fn dcache_csw(way_and_set: usize) {
     // th.dcache.csw {}
@@ -271,6 +321,15 @@ pub unsafe fn dcache_csw(way_and_set: usize) {
 /// The E906 core has a 2-way set-associative D-cache. Input variable `rs1[31]` represents number of way,
 /// while `rs1[w:6]` represents number of set. When D-cache is configured 32 Kibibytes, `w` equals 13;
 /// when configured 16 Kibibytes, `w` equals 12, and so on.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
 #[inline]
 pub unsafe fn dcache_isw(way_and_set: usize) {
     // th.dcache.isw
@@ -323,6 +382,15 @@ pub unsafe fn dcache_isw(way_and_set: usize) {
 /// The E906 core has a 2-way set-associative D-cache. Input variable `rs1[31]` represents number of way,
 /// while `rs1[w:6]` represents number of set. When D-cache is configured 32 Kibibytes, `w` equals 13;
 /// when configured 16 Kibibytes, `w` equals 12, and so on.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
 #[inline]
 pub unsafe fn dcache_cisw(way_and_set: usize) {
     // th.dcache.cisw
@@ -351,6 +419,7 @@ pub unsafe fn dcache_cisw(way_and_set: usize) {
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907 and C908 cores.
 /// On Xuantie C906 User Manual, this instruction is named `DCACHE.CVA`.
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908))]
 #[inline]
 pub unsafe fn dcache_cval1(va: usize) {
     // th.dcache.cval1
@@ -381,6 +450,7 @@ pub unsafe fn dcache_cval1(va: usize) {
 ///
 /// The Xuantie C906 User Manual names `DCACHE.CVAL1` as `DCACHE.CVA`; to clean dirty item on
 /// C906 you may need to use function [`dcache_cval1`] on this library.
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c907, xuantie_c908))]
 #[inline]
 pub unsafe fn dcache_cva(va: usize) {
     // th.dcache.cva
@@ -409,6 +479,7 @@ pub unsafe fn dcache_cva(va: usize) {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907 and C908 cores.
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908))]
 #[inline]
 pub unsafe fn dcache_iva(va: usize) {
     // th.dcache.iva
@@ -440,6 +511,7 @@ pub unsafe fn dcache_iva(va: usize) {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907 and C908 cores.
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908))]
 #[inline]
 pub unsafe fn dcache_civa(va: usize) {
     // th.dcache.civa
@@ -465,6 +537,15 @@ pub unsafe fn dcache_civa(va: usize) {
 /// This instruction is supported on Xuantie C920, C910, C906, C907, C908, E907 and E906 cores.
 /// On Xuantie C906 User Manual, Xuantie E907 User Manual and Xuantie E906 User Manual,
 /// this instruction is named `DCACHE.CPA`.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
 #[inline]
 pub unsafe fn dcache_cpal1(pa: usize) {
     // th.dcache.cpal1
@@ -492,6 +573,7 @@ pub unsafe fn dcache_cpal1(pa: usize) {
 /// The Xuantie C906 User Manual, Xuantie E907 User Manual and Xuantie E906 User Manual
 /// names `DCACHE.CPAL1` as `DCACHE.CPA`; to clean dirty item on
 /// these cores you may need to use function [`dcache_cpal1`] on this library.
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c907, xuantie_c908))]
 #[inline]
 pub unsafe fn dcache_cpa(pa: usize) {
     // th.dcache.cpa
@@ -515,6 +597,15 @@ pub unsafe fn dcache_cpa(pa: usize) {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907, C908, E907 and E906 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
 pub unsafe fn dcache_ipa(pa: usize) {
     // th.dcache.ipa
     asm!(".insn i 0x0B, 0, x0, {}, 0x02A", in(reg) pa)
@@ -538,6 +629,15 @@ pub unsafe fn dcache_ipa(pa: usize) {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907, C908, E907 and E906 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
 #[inline]
 pub unsafe fn dcache_cipa(pa: usize) {
     // th.dcache.cipa
@@ -568,6 +668,7 @@ pub unsafe fn dcache_cipa(pa: usize) {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907 and C908 cores.
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908))]
 #[inline]
 pub unsafe fn icache_iva(va: usize) {
     // th.icache.iva
@@ -591,6 +692,16 @@ pub unsafe fn icache_iva(va: usize) {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907, C908, E907, E906 and E902 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906,
+    xuantie_e902
+))]
 #[inline]
 pub unsafe fn icache_ipa(pa: usize) {
     // th.icache.ipa
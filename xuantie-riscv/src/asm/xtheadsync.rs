@@ -17,6 +17,15 @@ use core::arch::asm;
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907, C908, E907 and E906 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
 #[inline]
 pub unsafe fn sync() {
     // th.sync
@@ -41,6 +50,7 @@ pub unsafe fn sync() {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C907 and C908 cores.
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c907, xuantie_c908))]
 #[inline]
 pub unsafe fn sync_s() {
     // th.sync.s
@@ -65,6 +75,15 @@ pub unsafe fn sync_s() {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C906, C907, C908, E907 and E906 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
 #[inline]
 pub unsafe fn sync_i() {
     // th.sync.i
@@ -90,6 +109,7 @@ pub unsafe fn sync_i() {
 /// # Platform support
 ///
 /// This instruction is supported on Xuantie C920, C910, C907 and C908 cores.
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c907, xuantie_c908))]
 #[inline]
 pub unsafe fn sync_is() {
     // th.sync.is
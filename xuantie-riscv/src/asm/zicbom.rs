@@ -0,0 +1,88 @@
+use core::arch::asm;
+
+/// Cache block clean instruction.
+///
+/// Writes the cache block containing address `addr` to next level storage if it is dirty,
+/// in every cache of the coherence domain, and keeps it cached.
+///
+/// # Permissions
+///
+/// Can run on M mode. Can run on S mode when `menvcfg.CBCFE = 1`, and on U mode when
+/// `menvcfg.CBCFE = 1` and `senvcfg.CBCFE = 1`.
+///
+/// # Exceptions
+///
+/// Raises illegal instruction exception, store/AMO page fault exception or store/AMO access
+/// fault exception.
+///
+/// - When run on S mode with `menvcfg.CBCFE = 0`, or on U mode with `menvcfg.CBCFE = 0` or
+///   `senvcfg.CBCFE = 0`, this instruction raises illegal instruction exception.
+///
+/// # Platform support
+///
+/// This instruction is supported on Xuantie C907 and C908 cores, and on other cores
+/// implementing the Zicbom extension.
+#[inline]
+pub unsafe fn cbo_clean(addr: usize) {
+    // cbo.clean
+    asm!(".insn i 0x0F, 2, x0, {}, 1", in(reg) addr)
+}
+
+/// Cache block invalidate instruction.
+///
+/// Discards the cache block containing address `addr` from every cache of the coherence
+/// domain, without writing back dirty data.
+///
+/// # Permissions
+///
+/// Can run on M mode. Can run on S mode when `menvcfg.CBIE` is not `0b00`, and on U mode
+/// when neither `menvcfg.CBIE` nor `senvcfg.CBIE` is `0b00`.
+///
+/// When the `CBIE` field of a more privileged mode is `0b01`, this instruction writes back
+/// dirty data like [`cbo_flush`] instead of discarding it.
+///
+/// # Exceptions
+///
+/// Raises illegal instruction exception, store/AMO page fault exception or store/AMO access
+/// fault exception.
+///
+/// - When run on S mode with `menvcfg.CBIE = 0b00`, or on U mode with `menvcfg.CBIE = 0b00`
+///   or `senvcfg.CBIE = 0b00`, this instruction raises illegal instruction exception.
+///
+/// # Platform support
+///
+/// This instruction is supported on Xuantie C907 and C908 cores, and on other cores
+/// implementing the Zicbom extension.
+#[inline]
+pub unsafe fn cbo_inval(addr: usize) {
+    // cbo.inval
+    asm!(".insn i 0x0F, 2, x0, {}, 0", in(reg) addr)
+}
+
+/// Cache block flush instruction.
+///
+/// Writes the cache block containing address `addr` to next level storage if it is dirty,
+/// then discards it from every cache of the coherence domain.
+///
+/// # Permissions
+///
+/// Can run on M mode. Can run on S mode when `menvcfg.CBCFE = 1`, and on U mode when
+/// `menvcfg.CBCFE = 1` and `senvcfg.CBCFE = 1`.
+///
+/// # Exceptions
+///
+/// Raises illegal instruction exception, store/AMO page fault exception or store/AMO access
+/// fault exception.
+///
+/// - When run on S mode with `menvcfg.CBCFE = 0`, or on U mode with `menvcfg.CBCFE = 0` or
+///   `senvcfg.CBCFE = 0`, this instruction raises illegal instruction exception.
+///
+/// # Platform support
+///
+/// This instruction is supported on Xuantie C907 and C908 cores, and on other cores
+/// implementing the Zicbom extension.
+#[inline]
+pub unsafe fn cbo_flush(addr: usize) {
+    // cbo.flush
+    asm!(".insn i 0x0F, 2, x0, {}, 2", in(reg) addr)
+}
@@ -0,0 +1,23 @@
+use core::arch::asm;
+
+/// Instruction fetch fence instruction.
+///
+/// Ensures that subsequent instruction fetches on the current hart observe all stores
+/// this hart made to instruction memory before this instruction.
+///
+/// # Permissions
+///
+/// Can run on M, S or U mode.
+///
+/// # Exceptions
+///
+/// This instruction does not raise exceptions.
+///
+/// # Platform support
+///
+/// This instruction is supported on all Xuantie cores.
+#[inline]
+pub unsafe fn fence_i() {
+    // fence.i
+    asm!(".insn i 0x0F, 1, x0, x0, 0")
+}
@@ -0,0 +1,98 @@
+//! Bus error diagnostics.
+//!
+//! A load or store that receives a bus error response is reported in one of two ways:
+//!
+//! - as an access fault exception. `mtval` holds the faulting address. On E907 and E906
+//!   cores with `mhint.aee` cleared the exception is imprecise: `mepc` holds the PC of a later
+//!   instruction instead of the faulting load or store, see [`mhint`](crate::register::mhint);
+//! - on C920 and C910 cores, as the imprecise bus error interrupt. The faulting address is
+//!   held in [`mbeaddr`](crate::register::mbeaddr), and `mepc` holds the interrupted PC.
+//!
+//! [`BusErrorReport::capture`] combines these registers into one report from a trap handler.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xuantie_riscv::{bus_error::BusErrorReport, model};
+//! # fn log(_: usize, _: bool) {}
+//! // in the trap handler
+//! if let Some(report) = BusErrorReport::capture(model::detect()) {
+//!     log(report.address.unwrap_or(0), report.precise);
+//! }
+//! ```
+#[cfg(any(xuantie_c920, xuantie_c910))]
+use crate::register::mbeaddr;
+#[cfg(any(xuantie_e907, xuantie_e906, xuantie_e902))]
+use crate::register::mexstatus;
+#[cfg(any(xuantie_e907, xuantie_e906))]
+use crate::register::mhint;
+use crate::{csr, model::CoreModel};
+
+/// Load access fault exception code
+const LOAD_ACCESS_FAULT: usize = 5;
+/// Store or AMO access fault exception code
+const STORE_ACCESS_FAULT: usize = 7;
+/// Instruction access fault exception code
+const INSTRUCTION_ACCESS_FAULT: usize = 1;
+/// XuanTie imprecise bus error interrupt code
+const BUS_ERROR_INTERRUPT: usize = 16;
+
+/// Diagnostic information about a bus error trap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BusErrorReport {
+    /// Raw `mcause` value
+    pub mcause: usize,
+    /// Raw `mtval` value
+    pub mtval: usize,
+    /// Raw `mbeaddr` value, on cores implementing it
+    pub mbeaddr: Option<usize>,
+    /// `mexstatus.buserr`, on cores implementing it
+    pub buserr: Option<bool>,
+    /// Address of the access that caused the bus error, if known
+    pub address: Option<usize>,
+    /// Does `mepc` hold the PC of the instruction that caused the bus error
+    pub precise: bool,
+}
+
+impl BusErrorReport {
+    /// Read the trap registers of the current hart and build a bus error report.
+    ///
+    /// Returns `None` if `mcause` is not an access fault or a bus error interrupt. Only
+    /// registers implemented by `model` are read.
+    pub fn capture(model: CoreModel) -> Option<BusErrorReport> {
+        let mcause = csr::read::<0x342>();
+        let interrupt = mcause >> (usize::BITS - 1) != 0;
+        let code = mcause & !(1 << (usize::BITS - 1));
+        match (interrupt, code) {
+            (false, INSTRUCTION_ACCESS_FAULT | LOAD_ACCESS_FAULT | STORE_ACCESS_FAULT) => {}
+            (true, BUS_ERROR_INTERRUPT) => {}
+            _ => return None,
+        }
+        let mtval = csr::read::<0x343>();
+        let (mbeaddr, buserr, aee) = match model {
+            #[cfg(any(xuantie_c920, xuantie_c910))]
+            CoreModel::C910 | CoreModel::C920 => (Some(mbeaddr::read()), None, true),
+            #[cfg(any(xuantie_e907, xuantie_e906))]
+            CoreModel::E906 | CoreModel::E907 => {
+                let buserr = mexstatus::read().buserr();
+                (None, Some(buserr), mhint::read().aee())
+            }
+            #[cfg(xuantie_e902)]
+            CoreModel::E902 => (None, Some(mexstatus::read().buserr()), true),
+            _ => (None, None, true),
+        };
+        let (address, precise) = if interrupt {
+            (mbeaddr, false)
+        } else {
+            (Some(mtval), aee || code == INSTRUCTION_ACCESS_FAULT)
+        };
+        Some(BusErrorReport {
+            mcause,
+            mtval,
+            mbeaddr,
+            buserr,
+            address,
+            precise,
+        })
+    }
+}
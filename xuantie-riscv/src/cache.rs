@@ -0,0 +1,304 @@
+//! Cache geometry and maintenance.
+//!
+//! XuanTie cores are configurable in cache size, so routines that walk caches by set and way
+//! need the [`CacheGeometry`] of the core they run on. [`L1Geometry::detect`] returns the
+//! configuration of the current core, with cache sizes read from `mcpuid`.
+//!
+//! # Example
+//!
+//! Write back and invalidate the whole D-cache before powering down a hart:
+//!
+//! ```no_run
+//! use xuantie_riscv::cache::{self, L1Geometry};
+//! let geometry = L1Geometry::detect().expect("core has L1 caches");
+//! unsafe { cache::clean_invalidate_all_by_set_way(geometry.dcache) };
+//! ```
+use crate::{
+    model::CoreModel,
+    register::mcpuid::{self, Cpuid},
+};
+
+pub mod dump;
+mod instruction;
+pub use instruction::*;
+#[cfg(any(xuantie_c920, xuantie_c910))]
+mod l2;
+#[cfg(any(xuantie_c920, xuantie_c910))]
+pub use l2::*;
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+mod policy;
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+pub use policy::*;
+mod range;
+pub use range::*;
+
+/// Size and organization of one cache
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CacheGeometry {
+    /// Total size in bytes
+    pub size: usize,
+    /// Number of ways
+    pub ways: usize,
+    /// Number of sets
+    pub sets: usize,
+    /// Cache line size in bytes
+    pub line_size: usize,
+}
+
+impl CacheGeometry {
+    /// Create a cache geometry from its total size, number of ways and line size in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ways` or `line_size` is not a power of two, or `size` holds less than one
+    /// line per way.
+    #[inline]
+    pub const fn new(size: usize, ways: usize, line_size: usize) -> Self {
+        assert!(ways.is_power_of_two() && line_size.is_power_of_two());
+        assert!(size / ways / line_size != 0);
+        Self {
+            size,
+            ways,
+            sets: size / ways / line_size,
+            line_size,
+        }
+    }
+    /// Number of cache lines
+    #[inline]
+    pub const fn lines(&self) -> usize {
+        self.sets * self.ways
+    }
+    /// Same cache organization with another total size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` holds less than one line per way.
+    #[inline]
+    pub const fn with_size(self, size: usize) -> Self {
+        Self::new(size, self.ways, self.line_size)
+    }
+    /// Encode set and way as the operand of D-cache set/way instructions.
+    ///
+    /// The way number is placed in the highest bits of `rs1[31:0]`, that is `rs1[31]` for a
+    /// 2-way cache and `rs1[31:30]` for a 4-way cache, and the set number starts at the bit
+    /// above the line offset, `rs1[6]` for 64-byte lines. A direct-mapped cache has no way
+    /// bits.
+    #[inline]
+    pub const fn set_way(&self, set: usize, way: usize) -> usize {
+        let set_bits = set << self.line_size.trailing_zeros();
+        if self.ways == 1 {
+            return set_bits;
+        }
+        let way_shift = 32 - self.ways.trailing_zeros();
+        (way << way_shift) | set_bits
+    }
+}
+
+/// Geometry of the L1 I-cache and D-cache
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct L1Geometry {
+    /// Instruction cache
+    pub icache: CacheGeometry,
+    /// Data cache
+    pub dcache: CacheGeometry,
+}
+
+impl L1Geometry {
+    /// Default L1 cache configuration of a core model.
+    ///
+    /// All cores use 64-byte lines. The D-cache is 4-way set-associative on C906 and
+    /// 2-way set-associative on other cores. Returns `None` for cores without L1 caches and
+    /// unknown processors.
+    pub const fn for_model(model: CoreModel) -> Option<Self> {
+        const fn kib(n: usize) -> usize {
+            n * 1024
+        }
+        let (icache, dcache) = match model {
+            CoreModel::C906 => (
+                CacheGeometry::new(kib(32), 2, 64),
+                CacheGeometry::new(kib(32), 4, 64),
+            ),
+            CoreModel::C907 | CoreModel::C908 | CoreModel::E906 | CoreModel::E907 => (
+                CacheGeometry::new(kib(32), 2, 64),
+                CacheGeometry::new(kib(32), 2, 64),
+            ),
+            CoreModel::C910 | CoreModel::C920 => (
+                CacheGeometry::new(kib(64), 2, 64),
+                CacheGeometry::new(kib(64), 2, 64),
+            ),
+            CoreModel::E902 | CoreModel::Unknown { .. } => return None,
+        };
+        Some(Self { icache, dcache })
+    }
+    /// L1 cache configuration from processor information.
+    ///
+    /// Cache sizes reported by `mcpuid` replace the defaults of the core model.
+    pub fn from_cpuid(cpuid: &Cpuid) -> Option<Self> {
+        let mut ans = Self::for_model(CoreModel::from_cpuid(cpuid))?;
+        if let Some(size) = cpuid.icache_size() {
+            ans.icache = ans.icache.with_size(size);
+        }
+        if let Some(size) = cpuid.dcache_size() {
+            ans.dcache = ans.dcache.with_size(size);
+        }
+        Some(ans)
+    }
+    /// L1 cache configuration of the current core.
+    #[inline]
+    pub fn detect() -> Option<Self> {
+        Self::from_cpuid(&mcpuid::read())
+    }
+}
+
+/// Write back every dirty line of the D-cache, walking it by set and way.
+///
+/// Completes with `sync`, so that all write backs are done when this function returns.
+/// Only the current hart's D-cache is affected.
+///
+/// # Safety
+///
+/// Must run in M or S mode with `mxstatus.theadisaee` set, and `geometry` must describe
+/// the D-cache of this core.
+///
+/// # Platform support
+///
+/// This function is supported on Xuantie C920, C910, C906, C907, C908, E907 and E906 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
+#[inline]
+pub unsafe fn clean_all_by_set_way(geometry: CacheGeometry) {
+    for set in 0..geometry.sets {
+        for way in 0..geometry.ways {
+            crate::asm::dcache_csw(geometry.set_way(set, way));
+        }
+    }
+    crate::asm::sync();
+}
+
+/// Invalidate every line of the D-cache, discarding dirty data, walking it by set and way.
+///
+/// Completes with `sync`, so that every line is invalid when this function returns.
+/// Only the current hart's D-cache is affected.
+///
+/// # Safety
+///
+/// Dirty lines are discarded without being written back; the caller must ensure no
+/// memory still in use has unwritten data in the D-cache. Must run in M or S mode with
+/// `mxstatus.theadisaee` set, and `geometry` must describe the D-cache of this core.
+///
+/// # Platform support
+///
+/// This function is supported on Xuantie C920, C910, C906, C907, C908, E907 and E906 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
+#[inline]
+pub unsafe fn invalidate_all_by_set_way(geometry: CacheGeometry) {
+    for set in 0..geometry.sets {
+        for way in 0..geometry.ways {
+            crate::asm::dcache_isw(geometry.set_way(set, way));
+        }
+    }
+    crate::asm::sync();
+}
+
+/// Write back and invalidate every line of the D-cache, walking it by set and way.
+///
+/// Completes with `sync`, so that all write backs are done when this function returns.
+/// Only the current hart's D-cache is affected.
+///
+/// # Safety
+///
+/// Must run in M or S mode with `mxstatus.theadisaee` set, and `geometry` must describe
+/// the D-cache of this core.
+///
+/// # Platform support
+///
+/// This function is supported on Xuantie C920, C910, C906, C907, C908, E907 and E906 cores.
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
+#[inline]
+pub unsafe fn clean_invalidate_all_by_set_way(geometry: CacheGeometry) {
+    for set in 0..geometry.sets {
+        for way in 0..geometry.ways {
+            crate::asm::dcache_cisw(geometry.set_way(set, way));
+        }
+    }
+    crate::asm::sync();
+}
+
+#[cfg(all(test, not(any(target_arch = "riscv32", target_arch = "riscv64"))))]
+mod tests {
+    use super::CacheGeometry;
+
+    #[test]
+    fn geometry() {
+        let geometry = CacheGeometry::new(32 * 1024, 4, 64);
+        assert_eq!(geometry.sets, 128);
+        assert_eq!(geometry.lines(), 512);
+        assert_eq!(geometry.with_size(64 * 1024).sets, 256);
+    }
+
+    #[test]
+    fn set_way_two_ways() {
+        let geometry = CacheGeometry::new(64 * 1024, 2, 64);
+        assert_eq!(geometry.set_way(0, 0), 0);
+        assert_eq!(geometry.set_way(5, 0), 5 << 6);
+        assert_eq!(geometry.set_way(5, 1), (1 << 31) | (5 << 6));
+        assert_eq!(geometry.set_way(511, 1), (1 << 31) | (511 << 6));
+    }
+
+    #[test]
+    fn set_way_four_ways() {
+        let geometry = CacheGeometry::new(32 * 1024, 4, 64);
+        assert_eq!(geometry.set_way(5, 1), (1 << 30) | (5 << 6));
+        assert_eq!(geometry.set_way(5, 2), (2 << 30) | (5 << 6));
+        assert_eq!(geometry.set_way(127, 3), (3 << 30) | (127 << 6));
+    }
+
+    #[test]
+    fn set_way_direct_mapped() {
+        let geometry = CacheGeometry::new(16 * 1024, 1, 64);
+        assert_eq!(geometry.sets, 256);
+        assert_eq!(geometry.set_way(5, 0), 5 << 6);
+        assert_eq!(geometry.set_way(255, 0), 255 << 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ways_not_power_of_two() {
+        CacheGeometry::new(48 * 1024, 3, 64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn line_size_not_power_of_two() {
+        CacheGeometry::new(32 * 1024, 2, 48);
+    }
+
+    #[test]
+    #[should_panic]
+    fn less_than_one_line_per_way() {
+        CacheGeometry::new(64, 2, 64);
+    }
+}
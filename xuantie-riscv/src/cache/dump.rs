@@ -0,0 +1,199 @@
+//! L1 cache RAM dump through `mcindex`, `mcins` and `mcdata`.
+//!
+//! Walks every set and way of the I-cache or D-cache and reads back its tag and data RAMs,
+//! for example to check whether a DMA buffer is still cached or dirty after a device wrote
+//! to memory.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xuantie_riscv::cache::{dump, L1Geometry};
+//! let geometry = L1Geometry::detect().expect("core has L1 caches");
+//! for line in unsafe { dump::dcache(geometry.dcache) } {
+//!     if line.valid && line.dirty {
+//!         // address bits above the set index are in `line.tag`
+//!         let _ = (line.set, line.way, line.data());
+//!     }
+//! }
+//! ```
+use super::CacheGeometry;
+use crate::register::{
+    mcdata,
+    mcindex::{self, Mcindex, RID},
+    mcins,
+};
+
+/// Bytes returned by one data RAM read
+const CHUNK: usize = 16;
+
+/// Largest supported cache line size in bytes
+pub const MAX_LINE_SIZE: usize = 64;
+
+/// L1 cache to dump
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum L1Cache {
+    /// Instruction cache
+    Instruction,
+    /// Data cache
+    Data,
+}
+
+/// Contents of one L1 cache line
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CacheLineSnapshot {
+    /// Cache this line belongs to
+    pub cache: L1Cache,
+    /// Set index
+    pub set: u32,
+    /// Way number
+    pub way: u8,
+    /// Cache tag
+    pub tag: u32,
+    /// Line is valid
+    pub valid: bool,
+    /// Line is dirty; always false for I-cache
+    pub dirty: bool,
+    data: [u128; MAX_LINE_SIZE / CHUNK],
+    chunks: u8,
+}
+
+impl CacheLineSnapshot {
+    /// Line data, in 16-byte units from the lowest address.
+    #[inline]
+    pub fn data(&self) -> &[u128] {
+        &self.data[..self.chunks as usize]
+    }
+}
+
+/// Read one line of an L1 cache.
+///
+/// # Panics
+///
+/// Panics if `set` or `way` is out of range, or the line size is larger than
+/// [`MAX_LINE_SIZE`].
+///
+/// # Safety
+///
+/// Must run in M mode on a core with `mcins`, `mcindex` and `mcdata` registers, and no
+/// other code may use these registers meanwhile.
+pub unsafe fn snapshot(
+    cache: L1Cache,
+    geometry: CacheGeometry,
+    set: u32,
+    way: u8,
+) -> CacheLineSnapshot {
+    assert!((set as usize) < geometry.sets && (way as usize) < geometry.ways);
+    assert!(geometry.line_size <= MAX_LINE_SIZE);
+    let (tag_ram, data_ram) = match cache {
+        L1Cache::Instruction => (RID::ICacheTag, RID::ICacheData),
+        L1Cache::Data => (RID::DCacheTag, RID::DCacheData),
+    };
+    let index = set * geometry.line_size as u32;
+    visit(tag_ram, way, index);
+    let (tag, valid, dirty) = match cache {
+        L1Cache::Instruction => {
+            let t = mcdata::get_icache_tag();
+            (t.tag, t.valid, false)
+        }
+        L1Cache::Data => {
+            let t = mcdata::get_dcache_tag();
+            (t.tag, t.valid, t.dirty)
+        }
+    };
+    let chunks = geometry.line_size / CHUNK;
+    let mut data = [0; MAX_LINE_SIZE / CHUNK];
+    for (i, chunk) in data[..chunks].iter_mut().enumerate() {
+        visit(data_ram, way, index + (i * CHUNK) as u32);
+        *chunk = match cache {
+            L1Cache::Instruction => mcdata::get_icache_data().data,
+            L1Cache::Data => mcdata::get_dcache_data().data,
+        };
+    }
+    CacheLineSnapshot {
+        cache,
+        set,
+        way,
+        tag,
+        valid,
+        dirty,
+        data,
+        chunks: chunks as u8,
+    }
+}
+
+/// Iterate over every line of the I-cache.
+///
+/// # Safety
+///
+/// Same as [`snapshot`], for the whole time the iterator is used.
+#[inline]
+pub unsafe fn icache(geometry: CacheGeometry) -> Lines {
+    Lines::new(L1Cache::Instruction, geometry)
+}
+
+/// Iterate over every line of the D-cache.
+///
+/// # Safety
+///
+/// Same as [`snapshot`], for the whole time the iterator is used.
+#[inline]
+pub unsafe fn dcache(geometry: CacheGeometry) -> Lines {
+    Lines::new(L1Cache::Data, geometry)
+}
+
+/// Iterator over the lines of an L1 cache, set by set and way by way
+#[derive(Clone, Debug)]
+pub struct Lines {
+    cache: L1Cache,
+    geometry: CacheGeometry,
+    next: usize,
+}
+
+impl Lines {
+    #[inline]
+    fn new(cache: L1Cache, geometry: CacheGeometry) -> Self {
+        Self {
+            cache,
+            geometry,
+            next: 0,
+        }
+    }
+}
+
+impl Iterator for Lines {
+    type Item = CacheLineSnapshot;
+
+    #[inline]
+    fn next(&mut self) -> Option<CacheLineSnapshot> {
+        if self.next >= self.geometry.lines() {
+            return None;
+        }
+        let set = (self.next / self.geometry.ways) as u32;
+        let way = (self.next % self.geometry.ways) as u8;
+        self.next += 1;
+        Some(unsafe { snapshot(self.cache, self.geometry, set, way) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.geometry.lines().saturating_sub(self.next);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Lines {}
+
+/// Request a read of `ram` at `way` and `index`, and wait for the result in `mcdata`.
+#[inline]
+unsafe fn visit(ram: RID, way: u8, index: u32) {
+    mcindex::write(
+        Mcindex::from_bits(0)
+            .with_rid(ram)
+            .with_way_l1(way)
+            .with_index(index),
+    );
+    mcins::set_r();
+    while mcins::read().r() {
+        core::hint::spin_loop();
+    }
+}
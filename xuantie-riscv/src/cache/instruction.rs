@@ -0,0 +1,152 @@
+use super::{L1Geometry, RangeMaintenance};
+use crate::{
+    model::{Capabilities, CoreModel},
+    register::mcor::{self, Cache, Operation},
+};
+
+/// Make instructions written to `len` bytes from `ptr` visible to instruction fetch on
+/// the current hart.
+///
+/// Writes back the D-cache lines of the range, invalidates its I-cache lines and
+/// synchronizes the pipeline. When `mxstatus.theadisaee` is off, or the core has no known
+/// XTheadCmo address instructions, the whole D-cache is cleaned and the whole I-cache
+/// invalidated through `mcor`, followed by `fence.i`, instead.
+///
+/// # Safety
+///
+/// Must run in M mode, as it reads `mxstatus`. `ptr` and `len` must describe memory that
+/// may be read, and `caps` must have been probed on this core.
+///
+/// # Example
+///
+/// ```no_run
+/// use xuantie_riscv::{cache, model::Capabilities};
+/// let caps = unsafe { Capabilities::probe() };
+/// let code: &[u8] = &[0x13, 0x00, 0x00, 0x00]; // copied from a loaded module
+/// unsafe { cache::sync_instruction_range(&caps, code.as_ptr(), code.len()) };
+/// ```
+#[inline]
+pub unsafe fn sync_instruction_range(caps: &Capabilities, ptr: *const u8, len: usize) {
+    if !thead_isa_enabled() {
+        sync_by_mcor();
+        return;
+    }
+    if let Some(cmo) = RangeMaintenance::for_model(caps.model) {
+        cmo.clean_ptr_range(ptr, len);
+    }
+    let invalidated = match (caps.model, L1Geometry::for_model(caps.model)) {
+        #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908))]
+        (
+            CoreModel::C906 | CoreModel::C907 | CoreModel::C908 | CoreModel::C910 | CoreModel::C920,
+            Some(g),
+        ) => {
+            for line in lines(ptr as usize, len, g.icache.line_size) {
+                crate::asm::icache_iva(line);
+            }
+            true
+        }
+        #[cfg(any(xuantie_e907, xuantie_e906))]
+        (CoreModel::E906 | CoreModel::E907, Some(g)) => {
+            for line in lines(ptr as usize, len, g.icache.line_size) {
+                crate::asm::icache_ipa(line);
+            }
+            true
+        }
+        // no XTheadCmo address instructions known to be available
+        _ => false,
+    };
+    if invalidated {
+        complete(caps.model);
+    } else {
+        sync_by_mcor();
+    }
+}
+
+/// Make instructions written to `len` bytes from `ptr` visible to instruction fetch on
+/// all harts.
+///
+/// On cores where `sync.is` broadcasts, the D-cache lines of the range are written back,
+/// and the I-caches of all harts are invalidated with `icache.ialls` and `sync.is`.
+/// Otherwise this is [`sync_instruction_range`] on the current hart only.
+///
+/// Returns `false` if other harts may still fetch stale instructions, so that they must
+/// call [`sync_instruction_range`] themselves, for example on an inter-processor interrupt.
+/// Single-core processors always return `true`.
+///
+/// # Safety
+///
+/// Same as [`sync_instruction_range`].
+#[inline]
+pub unsafe fn sync_instruction_range_all_harts(
+    caps: &Capabilities,
+    ptr: *const u8,
+    len: usize,
+) -> bool {
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c907, xuantie_c908))]
+    if caps.sync_s_broadcast && thead_isa_enabled() {
+        if let Some(cmo) = RangeMaintenance::for_model(caps.model) {
+            cmo.clean_ptr_range(ptr, len);
+        }
+        crate::asm::icache_ialls();
+        crate::asm::sync_is();
+        return true;
+    }
+    sync_instruction_range(caps, ptr, len);
+    matches!(
+        caps.model,
+        CoreModel::E902 | CoreModel::E906 | CoreModel::E907 | CoreModel::C906
+    )
+}
+
+#[inline]
+fn thead_isa_enabled() -> bool {
+    crate::register::mxstatus::read().theadisaee()
+}
+
+#[inline]
+unsafe fn sync_by_mcor() {
+    mcor::cache(Cache::DATA, Operation::CLEAR);
+    mcor::wait();
+    mcor::cache(Cache::INSTRUCTION, Operation::INVALIDATE);
+    mcor::wait();
+    crate::asm::fence_i();
+}
+
+#[inline]
+unsafe fn complete(model: CoreModel) {
+    match model {
+        #[cfg(any(
+            xuantie_c920,
+            xuantie_c910,
+            xuantie_c906,
+            xuantie_c907,
+            xuantie_c908,
+            xuantie_e907,
+            xuantie_e906
+        ))]
+        CoreModel::E906
+        | CoreModel::E907
+        | CoreModel::C906
+        | CoreModel::C907
+        | CoreModel::C908
+        | CoreModel::C910
+        | CoreModel::C920 => crate::asm::sync_i(),
+        _ => crate::asm::fence_i(),
+    }
+}
+
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c907,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
+#[inline]
+fn lines(start: usize, len: usize, line_size: usize) -> impl Iterator<Item = usize> {
+    let mask = line_size - 1;
+    let end = if len == 0 { start } else { start + len };
+    ((start & !mask)..((end + mask) & !mask)).step_by(line_size)
+}
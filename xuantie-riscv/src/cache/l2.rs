@@ -0,0 +1,368 @@
+use crate::register::{
+    mccr2::{self, DLTNCY, IPRF, TLTNCY},
+    Reserved,
+};
+use bit_field::BitField;
+
+/// L2-cache configuration in `mccr2`, checked and written as a whole
+///
+/// RAM latencies, setup cycles and error correction must be configured before the L2-cache
+/// is enabled, and may not change while it is. [`apply`](Self::apply) validates the
+/// configuration against these rules and the one currently active, writes all of `mccr2`
+/// in one go and reads it back.
+///
+/// # Example
+///
+/// ```no_run
+/// use xuantie_riscv::{cache::L2Config, register::mccr2::{DLTNCY, IPRF, TLTNCY}};
+/// let config = L2Config::new()
+///     .with_data_latency(DLTNCY::Cycle3)
+///     .with_tag_latency(TLTNCY::Cycle2)
+///     .with_ecc(true)
+///     .with_refill(true)
+///     .with_instruction_prefetch(IPRF::Prefetch3Lines)
+///     .with_tlb_prefetch(true)
+///     .with_enabled(true);
+/// unsafe { config.apply() }.expect("L2-cache configuration");
+/// ```
+///
+/// # Platform support
+///
+/// This configuration is supported on Xuantie C920 and C910 cores.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct L2Config {
+    bits: u64,
+}
+
+/// L2-cache configuration rejected by [`L2Config::apply`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum L2ConfigError {
+    /// Tag RAM latency holds a reserved encoding
+    ReservedTagLatency(Reserved),
+    /// Latency, setup or error correction would change while the L2-cache is enabled
+    ChangedWhileEnabled,
+    /// The enabled L2-cache would be disabled, but it cannot be flushed on this core
+    ///
+    /// Flushing uses `l2cache.ciall`, which is only available on C910 cores and only
+    /// compiled in with the `c910` feature.
+    DisableWithoutFlush,
+    /// `mccr2` reads back differently from what was written
+    Mismatch {
+        /// Configuration written
+        expected: L2Config,
+        /// Configuration read back
+        actual: L2Config,
+    },
+}
+
+/// Fields that may only change while the L2-cache is disabled
+const STATIC_FIELDS: u64 = (1 << 1) | (0b111 << 16) | (1 << 19) | (0b111 << 22) | (1 << 25);
+
+impl L2Config {
+    /// Disabled L2-cache with the shortest latencies and all options off.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { bits: 0 }
+    }
+    /// Convert bit representation of `mccr2` and `mccr2h` into a configuration.
+    #[inline]
+    pub const fn from_bits(bits: u64) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of `mccr2` and `mccr2h`.
+    #[inline]
+    pub const fn bits(&self) -> u64 {
+        self.bits
+    }
+    /// Configuration currently active.
+    #[inline]
+    pub fn active() -> Self {
+        Self::from_bits(mccr2::read64())
+    }
+    /// Refill enable
+    #[inline]
+    pub fn refill(&self) -> bool {
+        self.bits.get_bit(0)
+    }
+    /// Error correction enable
+    #[inline]
+    pub fn ecc(&self) -> bool {
+        self.bits.get_bit(1)
+    }
+    /// L2-cache enable
+    #[inline]
+    pub fn enabled(&self) -> bool {
+        self.bits.get_bit(3)
+    }
+    /// Data RAM visit latency
+    #[inline]
+    pub fn data_latency(&self) -> DLTNCY {
+        mccr2::Mccr2::from_bits(self.bits as usize).dltncy()
+    }
+    /// Data RAM setup latency enable
+    #[inline]
+    pub fn data_setup(&self) -> bool {
+        self.bits.get_bit(19)
+    }
+    /// Tag RAM visit latency
+    #[inline]
+    pub fn tag_latency(&self) -> Result<TLTNCY, Reserved> {
+        mccr2::Mccr2::from_bits(self.bits as usize).tltncy()
+    }
+    /// Tag RAM setup latency enable
+    #[inline]
+    pub fn tag_setup(&self) -> bool {
+        self.bits.get_bit(25)
+    }
+    /// Instruction prefetch
+    #[inline]
+    pub fn instruction_prefetch(&self) -> IPRF {
+        mccr2::Mccr2::from_bits(self.bits as usize).iprf()
+    }
+    /// TLB prefetch enable
+    #[inline]
+    pub fn tlb_prefetch(&self) -> bool {
+        self.bits.get_bit(31)
+    }
+    /// PAE enable
+    #[inline]
+    pub fn pae(&self) -> bool {
+        self.bits.get_bit(32)
+    }
+    /// Set refill enable
+    #[inline]
+    pub fn with_refill(mut self, refill: bool) -> Self {
+        self.bits.set_bit(0, refill);
+        self
+    }
+    /// Set error correction enable
+    #[inline]
+    pub fn with_ecc(mut self, ecc: bool) -> Self {
+        self.bits.set_bit(1, ecc);
+        self
+    }
+    /// Set L2-cache enable
+    #[inline]
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.bits.set_bit(3, enabled);
+        self
+    }
+    /// Set data RAM visit latency
+    #[inline]
+    pub fn with_data_latency(mut self, latency: DLTNCY) -> Self {
+        self.bits.set_bits(16..=18, latency as u64);
+        self
+    }
+    /// Set data RAM setup latency enable
+    #[inline]
+    pub fn with_data_setup(mut self, setup: bool) -> Self {
+        self.bits.set_bit(19, setup);
+        self
+    }
+    /// Set tag RAM visit latency
+    #[inline]
+    pub fn with_tag_latency(mut self, latency: TLTNCY) -> Self {
+        self.bits.set_bits(22..=24, latency as u64);
+        self
+    }
+    /// Set tag RAM setup latency enable
+    #[inline]
+    pub fn with_tag_setup(mut self, setup: bool) -> Self {
+        self.bits.set_bit(25, setup);
+        self
+    }
+    /// Set instruction prefetch
+    #[inline]
+    pub fn with_instruction_prefetch(mut self, prefetch: IPRF) -> Self {
+        self.bits.set_bits(29..=30, prefetch as u64);
+        self
+    }
+    /// Set TLB prefetch enable
+    #[inline]
+    pub fn with_tlb_prefetch(mut self, prefetch: bool) -> Self {
+        self.bits.set_bit(31, prefetch);
+        self
+    }
+    /// Set PAE enable
+    #[inline]
+    pub fn with_pae(mut self, pae: bool) -> Self {
+        self.bits.set_bit(32, pae);
+        self
+    }
+    /// Check this configuration on its own.
+    #[inline]
+    pub fn validate(&self) -> Result<(), L2ConfigError> {
+        self.tag_latency()
+            .map_err(L2ConfigError::ReservedTagLatency)?;
+        Ok(())
+    }
+    /// Check that the active configuration can be changed to this one.
+    #[inline]
+    pub fn validate_transition(&self, active: L2Config) -> Result<(), L2ConfigError> {
+        self.validate()?;
+        let changed = (self.bits ^ active.bits) & STATIC_FIELDS != 0;
+        if changed && active.enabled() && self.enabled() {
+            return Err(L2ConfigError::ChangedWhileEnabled);
+        }
+        Ok(())
+    }
+    /// Whether this configuration is the active one.
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        *self == Self::active()
+    }
+    /// Validate, write and read back this configuration.
+    ///
+    /// Latency and error correction changes are allowed while the L2-cache is disabled,
+    /// or in the same write that enables or disables it. Before an enabled L2-cache is
+    /// disabled, the D-cache is cleaned and the L2-cache cleaned and invalidated, so that no
+    /// dirty data is lost; this is only possible on C910 cores, others return
+    /// [`DisableWithoutFlush`](L2ConfigError::DisableWithoutFlush). On RV32, `mccr2h` is
+    /// written before `mccr2`, so that the L2-cache enable bit takes effect last.
+    ///
+    /// # Safety
+    ///
+    /// Must run in M mode, and no other hart may use the L2-cache while it is reconfigured.
+    /// Disabling the L2-cache requires `mxstatus.theadisaee` to be set.
+    #[inline]
+    pub unsafe fn apply(&self) -> Result<(), L2ConfigError> {
+        let active = Self::active();
+        self.validate_transition(active)?;
+        if active.enabled() && !self.enabled() {
+            Self::flush()?;
+        }
+        mccr2::write64(self.bits);
+        let actual = Self::active();
+        if actual != *self {
+            return Err(L2ConfigError::Mismatch {
+                expected: *self,
+                actual,
+            });
+        }
+        Ok(())
+    }
+    /// Clean the D-cache, then clean and invalidate the L2-cache.
+    ///
+    /// Cache instructions are not simulated, so this always fails on non-RISC-V targets.
+    #[inline]
+    unsafe fn flush() -> Result<(), L2ConfigError> {
+        #[cfg(all(xuantie_c910, any(target_arch = "riscv32", target_arch = "riscv64")))]
+        if crate::model::detect() == crate::model::CoreModel::C910 {
+            crate::asm::dcache_call();
+            crate::asm::l2cache_ciall();
+            crate::asm::sync();
+            return Ok(());
+        }
+        Err(L2ConfigError::DisableWithoutFlush)
+    }
+}
+
+impl Default for L2Config {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, not(any(target_arch = "riscv32", target_arch = "riscv64"))))]
+mod tests {
+    use super::{L2Config, L2ConfigError};
+    use crate::register::{mccr2::DLTNCY, Reserved};
+    use crate::sim;
+
+    /// `mccr2` as left by C910 boot code: refill, L2-cache and all prefetches enabled
+    const BOOT: u64 = 0xE041_0009;
+
+    #[test]
+    fn validate() {
+        assert_eq!(L2Config::from_bits(BOOT).validate(), Ok(()));
+        assert_eq!(
+            L2Config::new().with_ecc(true).with_refill(true).validate(),
+            Ok(())
+        );
+        assert_eq!(
+            L2Config::from_bits(5 << 22).validate(),
+            Err(L2ConfigError::ReservedTagLatency(Reserved(5)))
+        );
+    }
+
+    #[test]
+    fn validate_transition() {
+        let enabled = L2Config::from_bits(BOOT);
+        let disabled = enabled.with_enabled(false);
+        // static fields change while the L2-cache is disabled or is being enabled
+        let slower = enabled.with_data_latency(DLTNCY::Cycle4);
+        assert_eq!(slower.validate_transition(disabled), Ok(()));
+        assert_eq!(
+            slower.with_enabled(false).validate_transition(disabled),
+            Ok(())
+        );
+        assert_eq!(
+            slower.validate_transition(enabled),
+            Err(L2ConfigError::ChangedWhileEnabled)
+        );
+        assert_eq!(
+            enabled.with_ecc(true).validate_transition(enabled),
+            Err(L2ConfigError::ChangedWhileEnabled)
+        );
+        // static fields may change in the same write that disables the L2-cache
+        assert_eq!(
+            slower.with_enabled(false).validate_transition(enabled),
+            Ok(())
+        );
+        // prefetch and refill are not static
+        assert_eq!(
+            enabled
+                .with_tlb_prefetch(false)
+                .validate_transition(enabled),
+            Ok(())
+        );
+        assert_eq!(
+            enabled.with_refill(false).validate_transition(enabled),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn apply() {
+        sim::reset();
+        let config = L2Config::from_bits(BOOT);
+        assert_eq!(unsafe { config.apply() }, Ok(()));
+        assert_eq!(sim::read(0x7C3) as u64, BOOT);
+        assert!(config.is_active());
+        // rejected configurations leave mccr2 unchanged
+        assert_eq!(
+            unsafe { config.with_data_latency(DLTNCY::Cycle4).apply() },
+            Err(L2ConfigError::ChangedWhileEnabled)
+        );
+        assert_eq!(sim::read(0x7C3) as u64, BOOT);
+    }
+
+    #[test]
+    fn apply_disable_without_flush() {
+        // mcpuid reads as zero, which is not a C910
+        sim::reset();
+        sim::write(0x7C3, BOOT as usize);
+        let disabled = L2Config::from_bits(BOOT).with_enabled(false);
+        assert_eq!(
+            unsafe { disabled.apply() },
+            Err(L2ConfigError::DisableWithoutFlush)
+        );
+        assert_eq!(sim::read(0x7C3) as u64, BOOT);
+    }
+
+    #[test]
+    fn apply_mismatch() {
+        sim::reset();
+        // hardware without TLB prefetch keeps the bit clear
+        sim::set_write_hook(0x7C3, Some(|value| value & !(1 << 31)));
+        let config = L2Config::from_bits(BOOT);
+        assert_eq!(
+            unsafe { config.apply() },
+            Err(L2ConfigError::Mismatch {
+                expected: config,
+                actual: config.with_tlb_prefetch(false),
+            })
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use crate::register::{
+    mcor::{self, Cache, Operation},
+    mhcr::{self, Mhcr},
+};
+use core::marker::PhantomData;
+
+/// D-cache write policy
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum CachePolicy {
+    /// D-cache enabled, writes stay in cache until lines are evicted or cleaned
+    WriteBack,
+    /// D-cache enabled, writes go to memory at once
+    WriteThrough,
+    /// D-cache disabled
+    Disabled,
+}
+
+impl CachePolicy {
+    /// Policy encoded in a `mhcr` value.
+    #[inline]
+    pub fn from_mhcr(mhcr: Mhcr) -> Self {
+        match (mhcr.de(), mhcr.wb()) {
+            (false, _) => CachePolicy::Disabled,
+            (true, true) => CachePolicy::WriteBack,
+            (true, false) => CachePolicy::WriteThrough,
+        }
+    }
+    /// Policy of the current hart's D-cache.
+    #[inline]
+    pub fn current() -> Self {
+        Self::from_mhcr(mhcr::read())
+    }
+}
+
+/// Change the D-cache policy of the current hart.
+///
+/// Leaving write-back writes back all dirty lines, so that no data is lost when the
+/// D-cache stops accepting or holding them. Enabling a disabled D-cache invalidates it
+/// first, so that no stale lines are hit. The previous `mhcr` value is restored when the
+/// returned guard is dropped, with the same maintenance.
+///
+/// # Safety
+///
+/// Must run in M mode. Code that relies on the D-cache policy of the current hart, such as
+/// another guard, must not run until the returned guard is dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// use xuantie_riscv::cache::{self, CachePolicy};
+/// unsafe fn program_flash() {
+///     let _guard = cache::set_policy(CachePolicy::Disabled);
+///     // ... flash writes and reads bypass the D-cache here
+/// } // previous D-cache policy is restored
+/// ```
+///
+/// # Platform support
+///
+/// This function is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+#[inline]
+pub unsafe fn set_policy(policy: CachePolicy) -> PolicyGuard {
+    let saved = mhcr::read();
+    transition(policy);
+    PolicyGuard {
+        saved,
+        _not_send: PhantomData,
+    }
+}
+
+/// Restores the D-cache configuration in `mhcr` when dropped
+///
+/// The guard is neither `Send` nor `Sync`, as it must be dropped on the hart whose `mhcr`
+/// it saved.
+#[must_use = "the previous cache policy is restored when the guard is dropped"]
+#[derive(Debug)]
+pub struct PolicyGuard {
+    saved: Mhcr,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl PolicyGuard {
+    /// Configuration to be restored
+    #[inline]
+    pub fn saved(&self) -> Mhcr {
+        self.saved
+    }
+}
+
+impl Drop for PolicyGuard {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            transition(CachePolicy::from_mhcr(self.saved));
+            mhcr::write(self.saved);
+        }
+    }
+}
+
+/// Move from the current policy to `to`, with cache maintenance in between.
+#[inline]
+unsafe fn transition(to: CachePolicy) {
+    let from = CachePolicy::current();
+    if from == to {
+        return;
+    }
+    match to {
+        CachePolicy::WriteBack => {
+            if from == CachePolicy::Disabled {
+                flush(Operation::INVALIDATE);
+                mhcr::set_wb();
+                mhcr::set_de();
+            } else {
+                mhcr::set_wb();
+            }
+        }
+        CachePolicy::WriteThrough => {
+            if from == CachePolicy::Disabled {
+                flush(Operation::INVALIDATE);
+                mhcr::clear_wb();
+                mhcr::set_de();
+            } else {
+                // stop creating dirty lines before writing back the existing ones
+                mhcr::clear_wb();
+                flush(Operation::CLEAR);
+            }
+        }
+        CachePolicy::Disabled => {
+            mhcr::clear_wb();
+            flush(Operation::CLEAR | Operation::INVALIDATE);
+            mhcr::clear_de();
+        }
+    }
+}
+
+#[inline]
+unsafe fn flush(op: Operation) {
+    mcor::cache(Cache::DATA, op);
+    mcor::wait();
+}
+
+#[cfg(all(test, not(any(target_arch = "riscv32", target_arch = "riscv64"))))]
+mod tests {
+    use super::{set_policy, transition, CachePolicy};
+    use crate::sim;
+    extern crate std;
+    use std::{cell::RefCell, vec::Vec};
+
+    /// `mhcr` and `mcor` writes made by code under test
+    #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+    enum Write {
+        /// `mhcr` value written
+        Mhcr(usize),
+        /// D-cache operation bits started through `mcor`
+        Mcor(usize),
+    }
+
+    std::thread_local! {
+        static LOG: RefCell<Vec<Write>> = const { RefCell::new(Vec::new()) };
+    }
+
+    const IE: usize = 1 << 0;
+    const DE: usize = 1 << 1;
+    const WA: usize = 1 << 2;
+    const WB: usize = 1 << 3;
+    const INV: usize = 1 << 4;
+    const CLR: usize = 1 << 5;
+
+    /// Start with `mhcr` holding `value`, logging all later writes.
+    fn setup(mhcr: usize) {
+        sim::reset();
+        LOG.with(|log| log.borrow_mut().clear());
+        sim::write(0x7C1, mhcr);
+        sim::set_write_hook(
+            0x7C1,
+            Some(|value| {
+                LOG.with(|log| log.borrow_mut().push(Write::Mhcr(value)));
+                value
+            }),
+        );
+        // cache operations complete instantly
+        sim::set_write_hook(
+            0x7C2,
+            Some(|value| {
+                if value & (INV | CLR) != 0 {
+                    LOG.with(|log| log.borrow_mut().push(Write::Mcor(value & (INV | CLR))));
+                }
+                value & !(INV | CLR)
+            }),
+        );
+    }
+
+    fn log() -> Vec<Write> {
+        LOG.with(|log| log.borrow().clone())
+    }
+
+    #[test]
+    fn disabled_to_write_back() {
+        setup(IE);
+        unsafe { transition(CachePolicy::WriteBack) };
+        assert_eq!(
+            log(),
+            [
+                Write::Mcor(INV),
+                Write::Mhcr(IE | WB),
+                Write::Mhcr(IE | WB | DE)
+            ]
+        );
+    }
+
+    #[test]
+    fn disabled_to_write_through() {
+        setup(IE);
+        unsafe { transition(CachePolicy::WriteThrough) };
+        assert_eq!(
+            log(),
+            [Write::Mcor(INV), Write::Mhcr(IE), Write::Mhcr(IE | DE)]
+        );
+    }
+
+    #[test]
+    fn write_back_to_write_through() {
+        setup(IE | DE | WB);
+        unsafe { transition(CachePolicy::WriteThrough) };
+        assert_eq!(log(), [Write::Mhcr(IE | DE), Write::Mcor(CLR)]);
+    }
+
+    #[test]
+    fn write_through_to_write_back() {
+        setup(IE | DE);
+        unsafe { transition(CachePolicy::WriteBack) };
+        assert_eq!(log(), [Write::Mhcr(IE | DE | WB)]);
+    }
+
+    #[test]
+    fn write_back_to_disabled() {
+        setup(IE | DE | WB);
+        unsafe { transition(CachePolicy::Disabled) };
+        assert_eq!(
+            log(),
+            [
+                Write::Mhcr(IE | DE),
+                Write::Mcor(CLR | INV),
+                Write::Mhcr(IE)
+            ]
+        );
+    }
+
+    #[test]
+    fn write_through_to_disabled() {
+        setup(IE | DE);
+        unsafe { transition(CachePolicy::Disabled) };
+        assert_eq!(
+            log(),
+            [
+                Write::Mhcr(IE | DE),
+                Write::Mcor(CLR | INV),
+                Write::Mhcr(IE)
+            ]
+        );
+    }
+
+    #[test]
+    fn same_policy() {
+        setup(IE | DE | WB);
+        unsafe { transition(CachePolicy::WriteBack) };
+        assert_eq!(log(), []);
+    }
+
+    #[test]
+    fn guard_restores() {
+        setup(IE | DE | WA | WB);
+        let guard = unsafe { set_policy(CachePolicy::Disabled) };
+        assert_eq!(guard.saved().bits(), IE | DE | WA | WB);
+        assert_eq!(CachePolicy::current(), CachePolicy::Disabled);
+        drop(guard);
+        assert_eq!(
+            log(),
+            [
+                Write::Mhcr(IE | DE | WA),
+                Write::Mcor(CLR | INV),
+                Write::Mhcr(IE | WA),
+                // drop
+                Write::Mcor(INV),
+                Write::Mhcr(IE | WA | WB),
+                Write::Mhcr(IE | WA | WB | DE),
+                Write::Mhcr(IE | DE | WA | WB),
+            ]
+        );
+        assert_eq!(sim::read(0x7C1), IE | DE | WA | WB);
+    }
+}
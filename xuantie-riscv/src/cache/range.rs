@@ -0,0 +1,326 @@
+use super::L1Geometry;
+use crate::model::CoreModel;
+use core::ops::Range;
+
+/// Cache maintenance instructions used on an address range
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RangeBackend {
+    /// XTheadCmo virtual address instructions, operating on L1 and L2 caches of all harts,
+    /// completed by `sync.s`
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c907, xuantie_c908))]
+    XTheadCmo,
+    /// XTheadCmo virtual address instructions, with `dcache.cval1` for cleaning,
+    /// completed by `sync`
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908))]
+    XTheadCmoL1,
+    /// XTheadCmo physical address instructions, completed by `sync`; for cores running
+    /// without address translation
+    #[cfg(any(
+        xuantie_c920,
+        xuantie_c910,
+        xuantie_c906,
+        xuantie_c907,
+        xuantie_c908,
+        xuantie_e907,
+        xuantie_e906
+    ))]
+    XTheadCmoPhysical,
+    /// Standard Zicbom `cbo.clean`, `cbo.inval` and `cbo.flush` instructions,
+    /// completed by `fence rw, rw`
+    Zicbom,
+}
+
+/// Address range cache maintenance.
+///
+/// Maintains D-cache contents for buffers shared with non-coherent devices, line by line.
+/// Lines only partially covered by an invalidated range are cleaned and invalidated
+/// instead, so that data next to the buffer is not lost.
+///
+/// # Example
+///
+/// ```no_run
+/// use xuantie_riscv::cache::RangeMaintenance;
+/// let cmo = RangeMaintenance::detect().expect("core supports cache maintenance");
+/// let mut buf = [0u8; 512];
+/// // before a device reads `buf`
+/// unsafe { cmo.clean_range(&buf) };
+/// // after a device wrote `buf`
+/// unsafe { cmo.invalidate_range(&mut buf) };
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RangeMaintenance {
+    backend: RangeBackend,
+    line_size: usize,
+}
+
+impl RangeMaintenance {
+    /// Create range maintenance with given instructions and D-cache line size in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line_size` is not a power of two.
+    ///
+    /// # Safety
+    ///
+    /// The current core must implement the instructions of `backend`, and `line_size` must
+    /// not be larger than its D-cache line size, or lines of a range would be skipped.
+    #[inline]
+    pub const unsafe fn new(backend: RangeBackend, line_size: usize) -> Self {
+        assert!(line_size.is_power_of_two());
+        Self { backend, line_size }
+    }
+    /// Range maintenance for a core model.
+    ///
+    /// C907 and C908 use Zicbom, C910 and C920 use XTheadCmo with broadcast to all harts, C906 uses
+    /// XTheadCmo L1 instructions, and E906 and E907 use XTheadCmo physical address
+    /// instructions. Returns `None` for cores without D-cache and unknown processors.
+    pub const fn for_model(model: CoreModel) -> Option<Self> {
+        let Some(geometry) = L1Geometry::for_model(model) else {
+            return None;
+        };
+        let backend = match model {
+            CoreModel::C907 | CoreModel::C908 => RangeBackend::Zicbom,
+            #[cfg(any(xuantie_c920, xuantie_c910))]
+            CoreModel::C910 | CoreModel::C920 => RangeBackend::XTheadCmo,
+            #[cfg(xuantie_c906)]
+            CoreModel::C906 => RangeBackend::XTheadCmoL1,
+            #[cfg(any(xuantie_e907, xuantie_e906))]
+            CoreModel::E906 | CoreModel::E907 => RangeBackend::XTheadCmoPhysical,
+            _ => return None,
+        };
+        // the backend matches `model`, and the line size is the one of its D-cache
+        Some(unsafe { Self::new(backend, geometry.dcache.line_size) })
+    }
+    /// Range maintenance for the current core.
+    #[inline]
+    pub fn detect() -> Option<Self> {
+        Self::for_model(crate::model::detect())
+    }
+    /// Instructions in use
+    #[inline]
+    pub const fn backend(&self) -> RangeBackend {
+        self.backend
+    }
+    /// D-cache line size in bytes
+    #[inline]
+    pub const fn line_size(&self) -> usize {
+        self.line_size
+    }
+    /// Write back dirty lines of `buf`, so that devices read its current contents.
+    ///
+    /// # Safety
+    ///
+    /// Must run in a privilege mode allowed to execute the backend instructions.
+    #[inline]
+    pub unsafe fn clean_range(&self, buf: &[u8]) {
+        self.clean_ptr_range(buf.as_ptr(), buf.len())
+    }
+    /// Discard cached lines of `buf`, so that the contents written by devices are read.
+    ///
+    /// # Safety
+    ///
+    /// Data written to `buf` by the CPU but not yet written back is lost. Must run in a
+    /// privilege mode allowed to execute the backend instructions.
+    #[inline]
+    pub unsafe fn invalidate_range(&self, buf: &mut [u8]) {
+        self.invalidate_ptr_range(buf.as_mut_ptr(), buf.len())
+    }
+    /// Write back and discard cached lines of `buf`.
+    ///
+    /// # Safety
+    ///
+    /// Must run in a privilege mode allowed to execute the backend instructions.
+    #[inline]
+    pub unsafe fn clean_invalidate_range(&self, buf: &[u8]) {
+        self.clean_invalidate_ptr_range(buf.as_ptr(), buf.len())
+    }
+    /// Write back dirty lines of `len` bytes from `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `len` must describe memory that may be read. Must run in a privilege mode
+    /// allowed to execute the backend instructions.
+    #[inline]
+    pub unsafe fn clean_ptr_range(&self, ptr: *const u8, len: usize) {
+        for line in self.lines(ptr as usize, len).step_by(self.line_size) {
+            self.line(Op::Clean, line);
+        }
+        self.complete();
+    }
+    /// Discard cached lines of `len` bytes from `ptr`.
+    ///
+    /// Partially covered lines at both edges are cleaned and invalidated.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `len` must describe memory that may be written; data written to it by the
+    /// CPU but not yet written back is lost. Must run in a privilege mode allowed to execute
+    /// the backend instructions.
+    #[inline]
+    pub unsafe fn invalidate_ptr_range(&self, ptr: *mut u8, len: usize) {
+        for (op, line) in self.invalidate_lines(ptr as usize, len) {
+            self.line(op, line);
+        }
+        self.complete();
+    }
+    /// Write back and discard cached lines of `len` bytes from `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `len` must describe memory that may be read. Must run in a privilege mode
+    /// allowed to execute the backend instructions.
+    #[inline]
+    pub unsafe fn clean_invalidate_ptr_range(&self, ptr: *const u8, len: usize) {
+        for line in self.lines(ptr as usize, len).step_by(self.line_size) {
+            self.line(Op::CleanInvalidate, line);
+        }
+        self.complete();
+    }
+    /// Line aligned addresses covering `len` bytes from `start`
+    #[inline]
+    fn lines(&self, start: usize, len: usize) -> Range<usize> {
+        if len == 0 {
+            return 0..0;
+        }
+        let mask = self.line_size - 1;
+        (start & !mask)..((start + len + mask) & !mask)
+    }
+    /// Operations invalidating `len` bytes from `start`, line by line
+    ///
+    /// Lines only partially covered by the range are cleaned and invalidated.
+    #[inline]
+    fn invalidate_lines(&self, start: usize, len: usize) -> impl Iterator<Item = (Op, usize)> {
+        let (end, line_size) = (start + len, self.line_size);
+        self.lines(start, len).step_by(line_size).map(move |line| {
+            if line < start || line + line_size > end {
+                (Op::CleanInvalidate, line)
+            } else {
+                (Op::Invalidate, line)
+            }
+        })
+    }
+    #[inline]
+    unsafe fn line(&self, op: Op, addr: usize) {
+        match (self.backend, op) {
+            #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c907, xuantie_c908))]
+            (RangeBackend::XTheadCmo, op) => match op {
+                Op::Clean => crate::asm::dcache_cva(addr),
+                Op::Invalidate => crate::asm::dcache_iva(addr),
+                Op::CleanInvalidate => crate::asm::dcache_civa(addr),
+            },
+            #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908))]
+            (RangeBackend::XTheadCmoL1, op) => match op {
+                Op::Clean => crate::asm::dcache_cval1(addr),
+                Op::Invalidate => crate::asm::dcache_iva(addr),
+                Op::CleanInvalidate => crate::asm::dcache_civa(addr),
+            },
+            #[cfg(any(
+                xuantie_c920,
+                xuantie_c910,
+                xuantie_c906,
+                xuantie_c907,
+                xuantie_c908,
+                xuantie_e907,
+                xuantie_e906
+            ))]
+            (RangeBackend::XTheadCmoPhysical, op) => match op {
+                Op::Clean => crate::asm::dcache_cpal1(addr),
+                Op::Invalidate => crate::asm::dcache_ipa(addr),
+                Op::CleanInvalidate => crate::asm::dcache_cipa(addr),
+            },
+            (RangeBackend::Zicbom, op) => match op {
+                Op::Clean => crate::asm::cbo_clean(addr),
+                Op::Invalidate => crate::asm::cbo_inval(addr),
+                Op::CleanInvalidate => crate::asm::cbo_flush(addr),
+            },
+        }
+    }
+    #[inline]
+    unsafe fn complete(&self) {
+        match self.backend {
+            #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c907, xuantie_c908))]
+            RangeBackend::XTheadCmo => crate::asm::sync_s(),
+            #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908))]
+            RangeBackend::XTheadCmoL1 => crate::asm::sync(),
+            #[cfg(any(
+                xuantie_c920,
+                xuantie_c910,
+                xuantie_c906,
+                xuantie_c907,
+                xuantie_c908,
+                xuantie_e907,
+                xuantie_e906
+            ))]
+            RangeBackend::XTheadCmoPhysical => crate::asm::sync(),
+            RangeBackend::Zicbom => core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Op {
+    Clean,
+    Invalidate,
+    CleanInvalidate,
+}
+
+#[cfg(all(test, not(any(target_arch = "riscv32", target_arch = "riscv64"))))]
+mod tests {
+    use super::{Op, RangeBackend, RangeMaintenance};
+    extern crate std;
+    use std::vec::Vec;
+
+    const CMO: RangeMaintenance = unsafe { RangeMaintenance::new(RangeBackend::Zicbom, 64) };
+
+    fn invalidate(start: usize, len: usize) -> Vec<(Op, usize)> {
+        CMO.invalidate_lines(start, len).collect()
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(CMO.lines(0x1000, 0x80), 0x1000..0x1080);
+        assert_eq!(CMO.lines(0x1010, 0x80), 0x1000..0x10C0);
+        assert_eq!(CMO.lines(0x1000, 1), 0x1000..0x1040);
+        assert_eq!(CMO.lines(0x103F, 2), 0x1000..0x1080);
+        assert_eq!(CMO.lines(0x1010, 0), 0..0);
+    }
+
+    #[test]
+    fn invalidate_aligned() {
+        assert_eq!(
+            invalidate(0x1000, 0x80),
+            [(Op::Invalidate, 0x1000), (Op::Invalidate, 0x1040)]
+        );
+    }
+
+    #[test]
+    fn invalidate_unaligned_start() {
+        assert_eq!(
+            invalidate(0x1010, 0x70),
+            [(Op::CleanInvalidate, 0x1000), (Op::Invalidate, 0x1040)]
+        );
+    }
+
+    #[test]
+    fn invalidate_unaligned_end() {
+        assert_eq!(
+            invalidate(0x1000, 0x50),
+            [(Op::Invalidate, 0x1000), (Op::CleanInvalidate, 0x1040)]
+        );
+    }
+
+    #[test]
+    fn invalidate_unaligned_both() {
+        assert_eq!(
+            invalidate(0x1010, 0xA0),
+            [
+                (Op::CleanInvalidate, 0x1000),
+                (Op::Invalidate, 0x1040),
+                (Op::CleanInvalidate, 0x1080)
+            ]
+        );
+        // within a single line
+        assert_eq!(invalidate(0x1010, 0x10), [(Op::CleanInvalidate, 0x1000)]);
+        assert_eq!(invalidate(0x1010, 0), []);
+    }
+}
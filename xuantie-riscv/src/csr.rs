@@ -0,0 +1,219 @@
+//! CSR access backend.
+//!
+//! On RISC-V targets every access compiles down to a single `csrr`, `csrw`, `csrs` or `csrc`
+//! instruction. On other targets accesses are routed to the simulated CSR file in `crate::sim`,
+//! so that register modules can be exercised in host unit tests. Without the `sim` feature,
+//! CSR accesses on other targets panic.
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+use core::arch::asm;
+
+#[cfg(all(
+    not(any(target_arch = "riscv32", target_arch = "riscv64")),
+    any(test, feature = "sim")
+))]
+use crate::sim;
+
+/// Stand-in for the simulated CSR file when the `sim` feature is off.
+#[cfg(not(any(
+    target_arch = "riscv32",
+    target_arch = "riscv64",
+    test,
+    feature = "sim"
+)))]
+mod sim {
+    pub fn hart_read(csr: u16) -> usize {
+        panic!("read of CSR {csr:#x} on a non-RISC-V target without the `sim` feature")
+    }
+    pub fn hart_write(csr: u16, _: usize) {
+        panic!("write of CSR {csr:#x} on a non-RISC-V target without the `sim` feature")
+    }
+}
+
+/// Reads CSR `N`.
+#[inline]
+pub fn read<const N: u16>() -> usize {
+    match () {
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        () => {
+            let bits: usize;
+            unsafe { asm!("csrr {0}, {csr}", out(reg) bits, csr = const N) };
+            bits
+        }
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        () => sim::hart_read(N),
+    }
+}
+
+/// Writes `bits` into CSR `N`.
+#[inline]
+pub unsafe fn write<const N: u16>(bits: usize) {
+    match () {
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        () => asm!("csrw {csr}, {0}", in(reg) bits, csr = const N),
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        () => sim::hart_write(N, bits),
+    }
+}
+
+/// Sets bits in `mask` of CSR `N`.
+#[inline]
+pub unsafe fn set<const N: u16>(mask: usize) {
+    match () {
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        () => asm!("csrs {csr}, {0}", in(reg) mask, csr = const N),
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        () => sim::hart_write(N, sim::hart_read(N) | mask),
+    }
+}
+
+/// Clears bits in `mask` of CSR `N`.
+#[inline]
+pub unsafe fn clear<const N: u16>(mask: usize) {
+    match () {
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        () => asm!("csrc {csr}, {0}", in(reg) mask, csr = const N),
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        () => sim::hart_write(N, sim::hart_read(N) & !mask),
+    }
+}
+
+/// Clears bits in `mask` of CSR `N` and returns the value it held before.
+///
+/// Reading and clearing is one `csrrc` instruction, so no update made by hardware in between
+/// can be lost.
+#[inline]
+pub unsafe fn read_clear<const N: u16>(mask: usize) -> usize {
+    match () {
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        () => {
+            let bits: usize;
+            asm!("csrrc {0}, {csr}, {1}", out(reg) bits, in(reg) mask, csr = const N);
+            bits
+        }
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        () => {
+            let bits = sim::hart_read(N);
+            sim::hart_write(N, bits & !mask);
+            bits
+        }
+    }
+}
+
+/// Replaces the bits of CSR `N` selected by `mask` with those of `value`.
+///
+/// The field is updated with a `csrs` followed by a `csrc`. Each of them is a single atomic
+/// read-modify-write, so changes made to bits outside `mask` by a trap handler running between
+/// the two instructions are never lost. Between the two instructions the field transiently
+/// holds the bitwise OR of its old and new values.
+#[inline]
+pub unsafe fn write_field<const N: u16>(mask: usize, value: usize) {
+    set::<N>(value & mask);
+    clear::<N>(!value & mask);
+}
+
+/// Reads the 64-bit value of CSR `LO`, whose high half is CSR `HI` on RV32.
+///
+/// On RV32 the halves are read high, low, high again, retrying until both high reads agree,
+/// so that a carry out of the low half of a running counter never yields a torn value.
+/// On RV64 `HI` is not accessed.
+#[inline]
+pub fn read64<const LO: u16, const HI: u16>() -> u64 {
+    match () {
+        #[cfg(target_pointer_width = "32")]
+        () => loop {
+            let hi = read::<HI>();
+            let lo = read::<LO>();
+            if hi == read::<HI>() {
+                break ((hi as u64) << 32) | lo as u64;
+            }
+        },
+        #[cfg(not(target_pointer_width = "32"))]
+        () => read::<LO>() as u64,
+    }
+}
+
+/// Writes the 64-bit `bits` into CSR `LO`, whose high half is CSR `HI` on RV32.
+///
+/// On RV32 the high half is written first. On RV64 `HI` is not accessed.
+#[inline]
+pub unsafe fn write64<const LO: u16, const HI: u16>(bits: u64) {
+    match () {
+        #[cfg(target_pointer_width = "32")]
+        () => {
+            write::<HI>((bits >> 32) as usize);
+            write::<LO>(bits as usize);
+        }
+        #[cfg(not(target_pointer_width = "32"))]
+        () => write::<LO>(bits as usize),
+    }
+}
+
+#[cfg(all(test, not(any(target_arch = "riscv32", target_arch = "riscv64"))))]
+mod tests {
+    use crate::sim;
+
+    #[test]
+    fn read_write() {
+        sim::reset();
+        sim::write(0x7C1, 0x1234);
+        assert_eq!(super::read::<0x7C1>(), 0x1234);
+        unsafe { super::write::<0x7C1>(0x5678) };
+        assert_eq!(sim::read(0x7C1), 0x5678);
+    }
+
+    #[test]
+    fn set_clear() {
+        sim::reset();
+        sim::write(0x7C1, 0b1010);
+        unsafe { super::set::<0x7C1>(0b0101) };
+        assert_eq!(sim::read(0x7C1), 0b1111);
+        unsafe { super::clear::<0x7C1>(0b0011) };
+        assert_eq!(sim::read(0x7C1), 0b1100);
+    }
+
+    #[test]
+    fn read_clear() {
+        sim::reset();
+        sim::write(0x7C8, 0b1110);
+        assert_eq!(unsafe { super::read_clear::<0x7C8>(0b0110) }, 0b1110);
+        assert_eq!(sim::read(0x7C8), 0b1000);
+    }
+
+    #[test]
+    fn write_field() {
+        sim::reset();
+        sim::write(0x7C3, 0xF00F);
+        unsafe { super::write_field::<0x7C3>(0b111 << 4, 0b101 << 4) };
+        assert_eq!(sim::read(0x7C3), 0xF05F);
+        // bits of `value` outside `mask` are ignored
+        unsafe { super::write_field::<0x7C3>(0b111 << 4, usize::MAX & !(0b111 << 4)) };
+        assert_eq!(sim::read(0x7C3), 0xF00F);
+    }
+
+    #[test]
+    fn write_hook() {
+        sim::reset();
+        sim::set_write_hook(0x7C2, Some(|value| value & !1));
+        unsafe { super::write::<0x7C2>(0b11) };
+        assert_eq!(sim::read(0x7C2), 0b10);
+        unsafe { super::set::<0x7C2>(0b01) };
+        assert_eq!(sim::read(0x7C2), 0b10);
+    }
+
+    #[test]
+    fn read64_write64() {
+        sim::reset();
+        unsafe { super::write64::<0xB03, 0xB83>(0x1234_5678_9ABC_DEF0) };
+        #[cfg(target_pointer_width = "64")]
+        {
+            assert_eq!(sim::read(0xB03), 0x1234_5678_9ABC_DEF0);
+            assert_eq!(sim::read(0xB83), 0);
+        }
+        #[cfg(target_pointer_width = "32")]
+        {
+            assert_eq!(sim::read(0xB03), 0x9ABC_DEF0);
+            assert_eq!(sim::read(0xB83), 0x1234_5678);
+        }
+        assert_eq!(super::read64::<0xB03, 0xB83>(), 0x1234_5678_9ABC_DEF0);
+    }
+}
@@ -0,0 +1,209 @@
+//! Buffers shared with DMA devices on non-coherent SoCs.
+//!
+//! On C906 and other XuanTie based SoCs without cache coherent DMA, the CPU and devices
+//! must hand buffers over with cache maintenance in between. A [`DmaBuffer`] is owned by
+//! the CPU and can be read and written through `Deref`. [`DmaBuffer::to_device`] writes
+//! back and invalidates its cache lines, and gives a [`DeviceBuffer`] implementing the
+//! `embedded-dma` buffer traits to pass to a DMA driver. [`DeviceBuffer::from_device`]
+//! invalidates the lines again once the transfer is done, so that the CPU reads what the
+//! device wrote.
+//!
+//! Long-lived descriptor rings are better mapped uncached, see [`Uncached`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xuantie_riscv::{cache::RangeMaintenance, dma::DmaBuffer};
+//! #[repr(C, align(64))]
+//! struct Rx([u8; 1536]);
+//! static mut RX: Rx = Rx([0; 1536]);
+//! let cmo = RangeMaintenance::detect().expect("core supports cache maintenance");
+//! let buf = DmaBuffer::new(unsafe { &mut (*core::ptr::addr_of_mut!(RX)).0 }, cmo);
+//! let transfer = buf.to_device();
+//! // let transfer = dma_driver.receive(transfer).wait();
+//! let buf = transfer.from_device();
+//! let _received = &buf[..];
+//! ```
+use crate::{cache::RangeMaintenance, paging};
+use core::{
+    mem,
+    ops::{Deref, DerefMut},
+    sync::atomic::{self, Ordering},
+};
+use embedded_dma::{ReadBuffer, ReadTarget, WriteBuffer, WriteTarget};
+
+/// Buffer shared with DMA devices, owned by the CPU
+pub struct DmaBuffer<T: ?Sized + 'static> {
+    inner: &'static mut T,
+    cmo: RangeMaintenance,
+}
+
+/// Slice shared with DMA devices, owned by the CPU
+pub type DmaSlice<W> = DmaBuffer<[W]>;
+
+/// Buffer shared with DMA devices, owned by a device
+pub struct DeviceBuffer<T: ?Sized + 'static> {
+    inner: &'static mut T,
+    cmo: RangeMaintenance,
+}
+
+impl<T: ?Sized> DmaBuffer<T> {
+    /// Share a buffer with DMA devices, using `cmo` for cache maintenance.
+    ///
+    /// The buffer must cover whole cache lines, so that invalidating it never discards
+    /// data next to it, and no neighbouring data shares a line the device writes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or the size of `inner` is not a multiple of the cache line size
+    /// of `cmo`.
+    #[inline]
+    pub fn new(inner: &'static mut T, cmo: RangeMaintenance) -> Self {
+        let (ptr, len) = byte_range(inner);
+        let mask = cmo.line_size() - 1;
+        assert!(ptr as usize & mask == 0 && len & mask == 0);
+        Self { inner, cmo }
+    }
+    /// Hand the buffer over to a device.
+    ///
+    /// Writes back and invalidates its cache lines, so that the device reads the current
+    /// contents, and no dirty line overwrites data written by the device later.
+    #[inline]
+    pub fn to_device(self) -> DeviceBuffer<T> {
+        atomic::fence(Ordering::SeqCst);
+        let (ptr, len) = byte_range(self.inner);
+        unsafe { self.cmo.clean_invalidate_ptr_range(ptr, len) };
+        DeviceBuffer {
+            inner: self.inner,
+            cmo: self.cmo,
+        }
+    }
+    /// Release the inner buffer.
+    #[inline]
+    pub fn into_inner(self) -> &'static mut T {
+        self.inner
+    }
+}
+
+impl<T: ?Sized> Deref for DmaBuffer<T> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &T {
+        self.inner
+    }
+}
+
+impl<T: ?Sized> DerefMut for DmaBuffer<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.inner
+    }
+}
+
+impl<T: ?Sized> DeviceBuffer<T> {
+    /// Take the buffer back from a device after the transfer completed.
+    ///
+    /// Invalidates its cache lines, dropping lines the CPU may have prefetched while the
+    /// device was writing.
+    #[inline]
+    pub fn from_device(self) -> DmaBuffer<T> {
+        let (ptr, len) = byte_range(self.inner);
+        unsafe { self.cmo.invalidate_ptr_range(ptr as *mut u8, len) };
+        atomic::fence(Ordering::SeqCst);
+        DmaBuffer {
+            inner: self.inner,
+            cmo: self.cmo,
+        }
+    }
+}
+
+unsafe impl<T: ReadTarget + ?Sized> ReadBuffer for DeviceBuffer<T> {
+    type Word = T::Word;
+    #[inline]
+    unsafe fn read_buffer(&self) -> (*const T::Word, usize) {
+        self.inner.as_read_buffer()
+    }
+}
+
+unsafe impl<T: WriteTarget + ?Sized> WriteBuffer for DeviceBuffer<T> {
+    type Word = T::Word;
+    #[inline]
+    unsafe fn write_buffer(&mut self) -> (*mut T::Word, usize) {
+        self.inner.as_write_buffer()
+    }
+}
+
+/// Buffer mapped uncached, shared by the CPU and devices without cache maintenance
+///
+/// Map the pages of the buffer with [`uncached_flags`] first. CPU accesses are then slower,
+/// but the buffer never needs to be handed over, which suits descriptor rings that both
+/// sides update all the time.
+pub struct Uncached<T: ?Sized + 'static> {
+    inner: &'static mut T,
+}
+
+impl<T: ?Sized> Uncached<T> {
+    /// Use a buffer through its uncached mapping.
+    ///
+    /// Writes back and invalidates the cache lines left by earlier cached accesses
+    /// to the same memory.
+    ///
+    /// # Safety
+    ///
+    /// `inner` must point into pages mapped with [`uncached_flags`].
+    #[inline]
+    pub unsafe fn new(inner: &'static mut T, cmo: RangeMaintenance) -> Self {
+        let (ptr, len) = byte_range(inner);
+        cmo.clean_invalidate_ptr_range(ptr, len);
+        Self { inner }
+    }
+    /// Release the inner buffer.
+    #[inline]
+    pub fn into_inner(self) -> &'static mut T {
+        self.inner
+    }
+}
+
+impl<T: ?Sized> Deref for Uncached<T> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &T {
+        self.inner
+    }
+}
+
+impl<T: ?Sized> DerefMut for Uncached<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.inner
+    }
+}
+
+unsafe impl<T: ReadTarget + ?Sized> ReadBuffer for Uncached<T> {
+    type Word = T::Word;
+    #[inline]
+    unsafe fn read_buffer(&self) -> (*const T::Word, usize) {
+        self.inner.as_read_buffer()
+    }
+}
+
+unsafe impl<T: WriteTarget + ?Sized> WriteBuffer for Uncached<T> {
+    type Word = T::Word;
+    #[inline]
+    unsafe fn write_buffer(&mut self) -> (*mut T::Word, usize) {
+        self.inner.as_write_buffer()
+    }
+}
+
+/// Page table entry flags for an uncached mapping of DMA memory.
+///
+/// Clears `CACHEABLE` and `BUFFER`, so that accesses go straight to memory.
+#[inline]
+pub const fn uncached_flags(flags: paging::Flags) -> paging::Flags {
+    flags.difference(paging::Flags::CACHEABLE.union(paging::Flags::BUFFER))
+}
+
+#[inline]
+fn byte_range<T: ?Sized>(inner: &T) -> (*const u8, usize) {
+    (inner as *const T as *const u8, mem::size_of_val(inner))
+}
@@ -0,0 +1,269 @@
+//! Cache ECC error handling.
+//!
+//! On C920 and C910 cores, cache RAM errors are recorded in `mcer` for the L1-caches and
+//! jTLB, and in `mcer2` for the L2-cache, and signalled through the ECC and bus error
+//! interrupt. [`Ecc::handle`] is meant to be called from its `THeadEccBusError` handler.
+//! It collects every valid error record, classifies it as corrected or fatal, acknowledges
+//! it, counts it per RAM and passes it to the registered callbacks.
+//!
+//! Counters only use atomic loads and stores, and are updated by the interrupt handler only.
+//!
+//! # Example
+//!
+//! ```
+//! # use xuantie_riscv::sim;
+//! use xuantie_riscv::ecc::{Ecc, ErrorRecord, Ram};
+//! use xuantie_riscv::register::mcer::RamId;
+//!
+//! fn log_corrected(record: &ErrorRecord) { /* log to the safety monitor */ }
+//! fn take_offline(record: &ErrorRecord) { /* isolate the failing RAM */ }
+//!
+//! static ECC: Ecc = Ecc::new().on_corrected(log_corrected).on_fatal(take_offline);
+//!
+//! #[unsafe(no_mangle)]
+//! extern "C" fn THeadEccBusError() {
+//!     unsafe { ECC.handle() };
+//! }
+//! # sim::write(0x7C8, 1 << 31 | 3 << 21);
+//! # THeadEccBusError();
+//! # assert_eq!(sim::read(0x7C8), 3 << 21);
+//! # assert_eq!(ECC.corrected(Ram::L1(RamId::DCacheData)), 1);
+//! # sim::write(0x7C8, 1 << 31 | 1 << 20);
+//! # THeadEccBusError();
+//! # assert_eq!(sim::read(0x7C8), 0);
+//! # assert_eq!(ECC.bus_errors(), 1);
+//! ```
+use crate::register::{
+    mcer::{self, RamId},
+    mcer2::{self, L2RAMID},
+    Reserved,
+};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+pub mod self_test;
+
+/// Cache or TLB RAM reporting an error
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ram {
+    /// L1-cache or jTLB RAM, reported in `mcer`
+    L1(RamId),
+    /// L2-cache RAM, reported in `mcer2`
+    L2(L2RAMID),
+}
+
+/// Error severity
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// Error was corrected by hardware
+    Corrected,
+    /// Error could not be corrected
+    Fatal,
+}
+
+/// Cache error record
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ErrorRecord {
+    /// RAM reporting the error, or its raw reserved RAM index
+    pub ram: Result<Ram, Reserved>,
+    /// Error severity
+    pub severity: Severity,
+    /// Index of the failing entry
+    pub index: u16,
+    /// Way of the failing entry
+    pub way: u8,
+}
+
+/// Callback receiving error records
+pub type Callback = fn(&ErrorRecord);
+
+/// Corrected and fatal error counts of one RAM
+struct Counts {
+    corrected: AtomicUsize,
+    fatal: AtomicUsize,
+}
+
+impl Counts {
+    const fn new() -> Self {
+        Counts {
+            corrected: AtomicUsize::new(0),
+            fatal: AtomicUsize::new(0),
+        }
+    }
+    fn count(&self, severity: Severity) {
+        let counter = match severity {
+            Severity::Corrected => &self.corrected,
+            Severity::Fatal => &self.fatal,
+        };
+        let count = counter.load(Ordering::Relaxed);
+        counter.store(count.saturating_add(1), Ordering::Relaxed);
+    }
+}
+
+/// Cache ECC error handler
+pub struct Ecc {
+    l1: [Counts; 6],
+    l2: [Counts; 3],
+    reserved: Counts,
+    bus: AtomicUsize,
+    on_corrected: Option<Callback>,
+    on_fatal: Option<Callback>,
+}
+
+impl Ecc {
+    /// Create an error handler with no callbacks and all counters at zero.
+    #[inline]
+    pub const fn new() -> Self {
+        Ecc {
+            l1: [const { Counts::new() }; 6],
+            l2: [const { Counts::new() }; 3],
+            reserved: Counts::new(),
+            bus: AtomicUsize::new(0),
+            on_corrected: None,
+            on_fatal: None,
+        }
+    }
+    /// Set callback for corrected errors
+    #[inline]
+    pub const fn on_corrected(mut self, callback: Callback) -> Self {
+        self.on_corrected = Some(callback);
+        self
+    }
+    /// Set callback for fatal errors
+    #[inline]
+    pub const fn on_fatal(mut self, callback: Callback) -> Self {
+        self.on_fatal = Some(callback);
+        self
+    }
+    /// Collect, acknowledge and dispatch all pending error records.
+    ///
+    /// Bus error records in `mcer` are acknowledged and counted in
+    /// [`bus_errors`](Self::bus_errors), without calling any callback. Returns the number of
+    /// records handled.
+    ///
+    /// # Safety
+    ///
+    /// Must run in M mode. Records are acknowledged, so no other code may collect them
+    /// meanwhile.
+    pub unsafe fn handle(&self) -> usize {
+        let mut handled = 0;
+        if let Some(entry) = take_l1_entry() {
+            match entry {
+                L1Record::Ecc(record) => self.dispatch(record),
+                L1Record::Bus => {
+                    let count = self.bus.load(Ordering::Relaxed);
+                    self.bus.store(count.saturating_add(1), Ordering::Relaxed);
+                }
+            }
+            handled += 1;
+        }
+        if let Some(record) = take_l2_record() {
+            self.dispatch(record);
+            handled += 1;
+        }
+        handled
+    }
+    /// Number of corrected errors reported by `ram`
+    #[inline]
+    pub fn corrected(&self, ram: Ram) -> usize {
+        self.counts(ram).corrected.load(Ordering::Relaxed)
+    }
+    /// Number of fatal errors reported by `ram`
+    #[inline]
+    pub fn fatal(&self, ram: Ram) -> usize {
+        self.counts(ram).fatal.load(Ordering::Relaxed)
+    }
+    /// Number of errors reported with a reserved RAM index
+    #[inline]
+    pub fn reserved(&self) -> usize {
+        self.reserved.corrected.load(Ordering::Relaxed)
+            + self.reserved.fatal.load(Ordering::Relaxed)
+    }
+    /// Number of bus errors recorded in `mcer`
+    ///
+    /// Their addresses are not recorded; see [`bus_error`](crate::bus_error).
+    #[inline]
+    pub fn bus_errors(&self) -> usize {
+        self.bus.load(Ordering::Relaxed)
+    }
+    fn counts(&self, ram: Ram) -> &Counts {
+        match ram {
+            Ram::L1(id) => &self.l1[id as usize],
+            Ram::L2(id) => &self.l2[id as usize],
+        }
+    }
+    fn dispatch(&self, record: ErrorRecord) {
+        match record.ram {
+            Ok(ram) => self.counts(ram).count(record.severity),
+            Err(_) => self.reserved.count(record.severity),
+        }
+        let callback = match record.severity {
+            Severity::Corrected => self.on_corrected,
+            Severity::Fatal => self.on_fatal,
+        };
+        if let Some(callback) = callback {
+            callback(&record);
+        }
+    }
+}
+
+/// Valid record taken out of `mcer`
+enum L1Record {
+    /// Cache or jTLB RAM error
+    Ecc(ErrorRecord),
+    /// Bus error response, reported in `mcer` without a RAM
+    Bus,
+}
+
+/// Take the valid L1 error record out of `mcer`, if any.
+unsafe fn take_l1_entry() -> Option<L1Record> {
+    let l1 = mcer::take();
+    if !l1.err_vld() {
+        return None;
+    }
+    if l1.bus_err() {
+        return Some(L1Record::Bus);
+    }
+    Some(L1Record::Ecc(ErrorRecord {
+        ram: l1.ramid().map(Ram::L1),
+        severity: if l1.err_fatal() {
+            Severity::Fatal
+        } else {
+            Severity::Corrected
+        },
+        index: l1.err_index(),
+        way: l1.err_way(),
+    }))
+}
+
+/// Take the valid L1 RAM error record out of `mcer`, if any.
+///
+/// Bus error records are acknowledged and skipped.
+unsafe fn take_l1_record() -> Option<ErrorRecord> {
+    match take_l1_entry()? {
+        L1Record::Ecc(record) => Some(record),
+        L1Record::Bus => None,
+    }
+}
+
+/// Take the L2 error record out of `mcer2`, if any.
+///
+/// Only uncorrectable L2-cache errors are recorded by hardware.
+unsafe fn take_l2_record() -> Option<ErrorRecord> {
+    let l2 = mcer2::take();
+    if !l2.ecc_err() {
+        return None;
+    }
+    Some(ErrorRecord {
+        ram: l2.ramid().map(Ram::L2),
+        severity: Severity::Fatal,
+        index: l2.err_index(),
+        way: l2.err_way(),
+    })
+}
+
+impl Default for Ecc {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,213 @@
+//! ECC self-test using the `meicr` and `meicr2` fault injection registers.
+//!
+//! Each test injects a 1-bit or 2-bit error into one cache RAM, accesses a test line so
+//! that the error is written and read back, and checks that `mcer` or `mcer2` reports it
+//! with the expected RAM and severity. L1 records must also point at the set of the accessed
+//! line and at a way of the tested cache. Afterwards the injection is disabled, the error
+//! record is acknowledged and the caches are cleaned and invalidated, so that no corrupted
+//! line remains cached.
+//!
+//! Tests poll the error registers, and keep the ECC and bus error interrupt masked in `mie`
+//! while they run. They must be run in machine mode with address translation off.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xuantie_riscv::ecc::self_test::{self, TestLine};
+//! static mut LINE: TestLine = TestLine::new();
+//! // startup diagnostic
+//! for (ram, severity, result) in unsafe { self_test::run_all(&mut *core::ptr::addr_of_mut!(LINE)) } {
+//!     assert!(result.is_ok(), "ECC self-test failed for {:?} {:?}", ram, severity);
+//! }
+//! ```
+use super::{take_l1_record, take_l2_record, ErrorRecord, Ram, Severity};
+use crate::{
+    cache::L1Geometry,
+    csr,
+    register::{
+        mcer::RamId,
+        mcor::{self, Cache, Operation},
+        meicr,
+        meicr2::{self, L2RAMID},
+        mhcr,
+    },
+};
+use core::ptr;
+
+/// Cache line sized and aligned buffer used to provoke cache accesses
+#[repr(C, align(64))]
+pub struct TestLine([usize; 64 / core::mem::size_of::<usize>()]);
+
+impl TestLine {
+    /// Create a zeroed test line.
+    #[inline]
+    pub const fn new() -> Self {
+        TestLine([0; 64 / core::mem::size_of::<usize>()])
+    }
+}
+
+impl Default for TestLine {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ECC self-test failure
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelfTestError {
+    /// This RAM and severity cannot be tested by provoking cache accesses
+    Unsupported,
+    /// No error was reported
+    NotDetected,
+    /// An error was reported with an unexpected RAM, severity, index or way
+    Mismatch(ErrorRecord),
+}
+
+/// ECC and bus error interrupt enable bit in `mie`
+const MIE_ECC_BUS_ERROR: usize = 1 << 16;
+
+/// Number of tests run by [`run_all`]
+pub const TEST_COUNT: usize = 11;
+
+/// RAMs and severities tested by [`run_all`]
+const TESTS: [(Ram, Severity); TEST_COUNT] = [
+    (Ram::L1(RamId::ICacheTag), Severity::Corrected),
+    (Ram::L1(RamId::ICacheTag), Severity::Fatal),
+    (Ram::L1(RamId::ICacheData), Severity::Corrected),
+    (Ram::L1(RamId::ICacheData), Severity::Fatal),
+    (Ram::L1(RamId::DCacheTag), Severity::Corrected),
+    (Ram::L1(RamId::DCacheTag), Severity::Fatal),
+    (Ram::L1(RamId::DCacheData), Severity::Corrected),
+    (Ram::L1(RamId::DCacheData), Severity::Fatal),
+    (Ram::L2(L2RAMID::L2CacheTag), Severity::Fatal),
+    (Ram::L2(L2RAMID::L2CacheData), Severity::Fatal),
+    (Ram::L2(L2RAMID::L2CacheDirty), Severity::Fatal),
+];
+
+/// Test error detection of one cache RAM.
+///
+/// Returns the error record reported by hardware. jTLB RAMs and corrected L2-cache errors,
+/// which hardware does not record, are [`Unsupported`](SelfTestError::Unsupported).
+///
+/// # Safety
+///
+/// Must run in M mode on a single hart, while no other code uses the tested RAM, `mhcr`
+/// or the error reporting registers. I-cache and D-cache are enabled for the test, and
+/// their previous `mhcr` configuration is restored afterwards. Injected errors corrupt
+/// the tested cache line, which is invalidated afterwards.
+pub unsafe fn run(
+    ram: Ram,
+    severity: Severity,
+    line: &mut TestLine,
+) -> Result<ErrorRecord, SelfTestError> {
+    let fatal = severity == Severity::Fatal;
+    match ram {
+        Ram::L1(RamId::JTlbTag | RamId::JTlbData) => return Err(SelfTestError::Unsupported),
+        Ram::L2(_) if !fatal => return Err(SelfTestError::Unsupported),
+        _ => {}
+    }
+    let mie = csr::read::<0x304>() & MIE_ECC_BUS_ERROR;
+    csr::clear::<0x304>(mie);
+    let hcr = mhcr::read();
+    // start from a clean state, so that only the test line is affected
+    flush(Cache::BOTH);
+    mhcr::modify(|r| r.with_ie(true).with_de(true));
+    take_l1_record();
+    take_l2_record();
+    match ram {
+        Ram::L1(id) => meicr::write(true, fatal, id),
+        Ram::L2(id) => meicr2::write(true, fatal, id),
+    }
+    let addr = match ram {
+        Ram::L1(RamId::ICacheTag | RamId::ICacheData) => probe as fn() as usize,
+        _ => line as *const TestLine as usize,
+    };
+    match ram {
+        Ram::L1(RamId::ICacheTag | RamId::ICacheData) => {
+            probe();
+        }
+        Ram::L1(_) => {
+            touch(line);
+        }
+        Ram::L2(_) => {
+            // fill the line into L2-cache, then evict it from D-cache so that it is read back from L2
+            touch(line);
+            flush(Cache::DATA);
+            touch(line);
+        }
+    }
+    meicr::clear_inj_en();
+    meicr2::clear_inj_en();
+    let record = match ram {
+        Ram::L1(_) => take_l1_record(),
+        Ram::L2(_) => take_l2_record(),
+    };
+    flush(Cache::BOTH);
+    mhcr::write(hcr);
+    take_l1_record();
+    take_l2_record();
+    csr::set::<0x304>(mie);
+    match record {
+        None => Err(SelfTestError::NotDetected),
+        Some(record)
+            if record.ram == Ok(ram) && record.severity == severity && located(&record, addr) =>
+        {
+            Ok(record)
+        }
+        Some(record) => Err(SelfTestError::Mismatch(record)),
+    }
+}
+
+/// Test error detection of every cache RAM, with both severities.
+///
+/// Runs all [`TEST_COUNT`] tests of a fixed table, covering every RAM and severity that
+/// hardware records, before returning each RAM and severity along with its test result.
+///
+/// # Safety
+///
+/// Same as [`run`].
+pub unsafe fn run_all(
+    line: &mut TestLine,
+) -> [(Ram, Severity, Result<ErrorRecord, SelfTestError>); TEST_COUNT] {
+    TESTS.map(|(ram, severity)| (ram, severity, run(ram, severity, line)))
+}
+
+/// Whether an L1 record points at the set of `addr` and at a way of the tested cache.
+///
+/// `err_index` holds the set of the failing line. Which way a line is filled into is up to
+/// the hardware, so only its range is checked. L2 records, and L1 records on cores of unknown
+/// geometry, are not checked.
+fn located(record: &ErrorRecord, addr: usize) -> bool {
+    let geometry = match (record.ram, L1Geometry::detect()) {
+        (Ok(Ram::L1(RamId::ICacheTag | RamId::ICacheData)), Some(l1)) => l1.icache,
+        (Ok(Ram::L1(_)), Some(l1)) => l1.dcache,
+        _ => return true,
+    };
+    let set = addr / geometry.line_size % geometry.sets;
+    record.index as usize == set && (record.way as usize) < geometry.ways
+}
+
+/// Clean and invalidate selected caches, and wait for completion.
+#[inline]
+unsafe fn flush(cache: Cache) {
+    mcor::cache(cache, Operation::CLEAR | Operation::INVALIDATE);
+    mcor::wait();
+}
+
+/// Write and read back every word of the test line.
+#[inline]
+fn touch(line: &mut TestLine) {
+    for (i, word) in line.0.iter_mut().enumerate() {
+        unsafe {
+            ptr::write_volatile(word, i);
+            ptr::read_volatile(word);
+        }
+    }
+}
+
+/// Function fetched from a freshly invalidated I-cache.
+#[inline(never)]
+fn probe() {
+    core::hint::black_box(());
+}
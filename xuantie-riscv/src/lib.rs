@@ -65,14 +65,58 @@
 //!     unreachable!()
 //! }
 //! ```
+//!
+//! ## Restrict APIs to the target cores
+//!
+//! Enable one or more of the `c906`, `c907`, `c908`, `c910`, `c920`, `e902`, `e906` and `e907`
+//! Cargo features to hide instructions, registers and register bits that none of the selected
+//! cores implement, so that using them is a compile error instead of an illegal instruction
+//! exception at runtime. When no core feature is enabled, every API is available.
+//!
+//! ```toml
+//! [dependencies]
+//! xuantie-riscv = { version = "0.0.0", features = ["c906"] }
+//! ```
+//!
+//! ## Unit test register logic on the host
+//!
+//! When built for a non-RISC-V target with the `sim` feature, register accesses go to an
+//! in-memory CSR file instead of real hardware. See the `sim` module for how to preload
+//! and inspect it.
+//!
+//! ```toml
+//! [dev-dependencies]
+//! xuantie-riscv = { version = "0.0.0", features = ["sim"] }
+//! ```
 #![no_std]
 
 #[macro_use]
 mod macros;
 pub mod asm;
+pub mod bus_error;
+pub mod cache;
+mod csr;
 pub mod debug;
+pub mod dma;
+#[cfg(any(xuantie_c920, xuantie_c910))]
+pub mod ecc;
+pub mod model;
 pub mod paging;
 pub mod peripheral;
+#[cfg(any(
+    xuantie_c920,
+    xuantie_c910,
+    xuantie_c906,
+    xuantie_c908,
+    xuantie_e907,
+    xuantie_e906
+))]
+pub mod pmu;
 
 #[rustfmt::skip]
 pub mod register;
+#[cfg(all(
+    not(any(target_arch = "riscv32", target_arch = "riscv64")),
+    any(test, feature = "sim")
+))]
+pub mod sim;
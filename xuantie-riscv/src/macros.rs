@@ -3,17 +3,8 @@ macro_rules! read_csr_as {
         /// Reads the CSR
         #[inline]
         pub fn read() -> $register {
-            match () {
-                #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
-                () => {
-                    let bits: usize;
-                    unsafe { core::arch::asm!(concat!("csrr {}, ",$csr_number), out(reg) bits) };
-                    $register { bits }
-                }
-                #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
-                () => {
-                    unimplemented!()
-                }
+            $register {
+                bits: crate::csr::read::<{ $csr_number }>(),
             }
         }
     };
@@ -24,16 +15,7 @@ macro_rules! set_csr {
         $(#[$attr])*
         #[inline]
         pub unsafe fn $set_field() {
-            match () {
-                #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
-                () => {
-                    core::arch::asm!(concat!("csrs ",$csr_number,", {0}"), in(reg) $e)
-                }
-                #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
-                () => {
-                    unimplemented!()
-                }
-            }
+            crate::csr::set::<{ $csr_number }>($e)
         }
     }
 }
@@ -43,16 +25,7 @@ macro_rules! clear_csr {
         $(#[$attr])*
         #[inline]
         pub unsafe fn $clear_field() {
-            match () {
-                #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
-                () => {
-                    core::arch::asm!(concat!("csrc ",$csr_number,", {0}"), in(reg) $e)
-                }
-                #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
-                () => {
-                    unimplemented!()
-                }
-            }
+            crate::csr::clear::<{ $csr_number }>($e)
         }
     }
 }
@@ -63,19 +36,173 @@ macro_rules! set_clear_csr {
         clear_csr!($(#[$attr])*, $csr_number, $clear_field, $e);
     }
 }
+#[allow(unused_macros)] // only used by registers of some cores
 macro_rules! get_csr_value {
     ($csr_number:expr) => {
-        match () {
-            #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
-            () => {
-                let r: usize;
-                core::arch::asm!(concat!("csrr {}, ",$csr_number), out(reg) r);
-                r
+        crate::csr::read::<{ $csr_number }>()
+    };
+}
+
+macro_rules! write_csr_as {
+    ($register:ident, $csr_number:expr) => {
+        /// Writes the CSR
+        ///
+        /// # Safety
+        ///
+        /// Every field of the CSR is replaced; the caller must ensure the new value is valid
+        /// for this core and that code running afterwards does not rely on the old one.
+        #[inline]
+        pub unsafe fn write(value: $register) {
+            crate::csr::write::<{ $csr_number }>(value.bits)
+        }
+
+        /// Reads the CSR, applies `f` to its value and writes the result back
+        ///
+        /// Only the bits changed by `f` are written, using one `csrs` and one `csrc`,
+        /// so changes to other bits made by a trap handler meanwhile are not lost.
+        ///
+        /// # Safety
+        ///
+        /// Same as [`write`](fn@write): the fields changed by `f` must be valid for this core.
+        #[inline]
+        pub unsafe fn modify<F: FnOnce($register) -> $register>(f: F) {
+            let old = read();
+            let new = f(old);
+            crate::csr::set::<{ $csr_number }>(new.bits & !old.bits);
+            crate::csr::clear::<{ $csr_number }>(old.bits & !new.bits);
+        }
+
+        impl crate::register::Register for $register {
+            #[inline]
+            fn from_bits(bits: usize) -> Self {
+                Self { bits }
+            }
+            #[inline]
+            fn bits(&self) -> usize {
+                self.bits
+            }
+            #[inline]
+            fn read() -> Self {
+                read()
+            }
+            #[inline]
+            unsafe fn write(value: Self) {
+                write(value)
+            }
+            #[inline]
+            unsafe fn modify<F: FnOnce(Self) -> Self>(f: F) {
+                modify(f)
+            }
+        }
+    };
+}
+
+#[allow(unused_macros)] // only used by registers of some cores
+macro_rules! counter_csr {
+    ($(#[$attr:meta])* $register:ident, $csr_number:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[repr(transparent)]
+        pub struct $register {
+            bits: usize,
+        }
+
+        impl $register {
+            /// Convert bit representation into register value, keeping all the bits.
+            #[inline]
+            pub const fn from_bits(bits: usize) -> Self {
+                Self { bits }
+            }
+            /// Returns the raw bits of the register.
+            #[inline]
+            pub const fn bits(&self) -> usize {
+                self.bits
+            }
+            /// Cycle counter bit
+            #[inline]
+            pub fn cy(&self) -> bool {
+                bit_field::BitField::get_bit(&self.bits, 0)
+            }
+            /// Instructions-retired counter bit
+            #[inline]
+            pub fn ir(&self) -> bool {
+                bit_field::BitField::get_bit(&self.bits, 2)
+            }
+            /// Hardware performance monitor counter bit
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is not in `3..=31`.
+            #[inline]
+            pub fn hpm(&self, index: usize) -> bool {
+                assert!((3..=31).contains(&index));
+                bit_field::BitField::get_bit(&self.bits, index)
+            }
+            /// Set cycle counter bit
+            #[inline]
+            pub fn with_cy(mut self, cy: bool) -> Self {
+                bit_field::BitField::set_bit(&mut self.bits, 0, cy);
+                self
+            }
+            /// Set instructions-retired counter bit
+            #[inline]
+            pub fn with_ir(mut self, ir: bool) -> Self {
+                bit_field::BitField::set_bit(&mut self.bits, 2, ir);
+                self
             }
-            #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
-            () => {
-                unimplemented!()
+            /// Set hardware performance monitor counter bit
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is not in `3..=31`.
+            #[inline]
+            pub fn with_hpm(mut self, index: usize, hpm: bool) -> Self {
+                assert!((3..=31).contains(&index));
+                bit_field::BitField::set_bit(&mut self.bits, index, hpm);
+                self
             }
         }
+
+        read_csr_as!($register, $csr_number);
+        write_csr_as!($register, $csr_number);
+
+        set_clear_csr! {
+            /// Cycle counter bit
+            , $csr_number, set_cy, clear_cy, 1 << 0
+        }
+        set_clear_csr! {
+            /// Instructions-retired counter bit
+            , $csr_number, set_ir, clear_ir, 1 << 2
+        }
+
+        /// Set hardware performance monitor counter bit
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is not in `3..=31`.
+        ///
+        /// # Safety
+        ///
+        /// Must be called in a privilege mode that may write this CSR.
+        #[inline]
+        pub unsafe fn set_hpm(index: usize) {
+            assert!((3..=31).contains(&index));
+            crate::csr::set::<{ $csr_number }>(1 << index)
+        }
+
+        /// Clear hardware performance monitor counter bit
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is not in `3..=31`.
+        ///
+        /// # Safety
+        ///
+        /// Must be called in a privilege mode that may write this CSR.
+        #[inline]
+        pub unsafe fn clear_hpm(index: usize) {
+            assert!((3..=31).contains(&index));
+            crate::csr::clear::<{ $csr_number }>(1 << index)
+        }
     };
 }
@@ -0,0 +1,324 @@
+//! XuanTie core model identification.
+//!
+//! Firmware that boots on several XuanTie processors can [`detect`] the core it runs on
+//! and pick its configuration at runtime. [`Capabilities`] further reports which optional
+//! features the core implements, so that drivers can choose between code paths instead of
+//! taking illegal instruction exceptions.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xuantie_riscv::model::{self, CoreModel};
+//! match model::detect() {
+//!     CoreModel::C906 | CoreModel::C908 => { /* single-core application processor setup */ }
+//!     CoreModel::C910 | CoreModel::C920 => { /* multi-core setup with shared L2-cache */ }
+//!     _ => { /* conservative defaults */ }
+//! }
+//! ```
+use crate::{
+    csr,
+    register::mcpuid::{self, Arch, Cpuid},
+};
+
+/// XuanTie core model
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum CoreModel {
+    /// XuanTie E902
+    E902,
+    /// XuanTie E906
+    E906,
+    /// XuanTie E907
+    E907,
+    /// XuanTie C906
+    C906,
+    /// XuanTie C907
+    C907,
+    /// XuanTie C908
+    C908,
+    /// XuanTie C910
+    C910,
+    /// XuanTie C920
+    C920,
+    /// Processor not recognised, with its raw `mcpuid` family and class if present
+    Unknown {
+        /// Processor family
+        family: Option<u8>,
+        /// Processor class
+        class: Option<u8>,
+    },
+}
+
+/// E series family code
+const FAMILY_E: u8 = 0b0001;
+/// C series family code
+const FAMILY_C: u8 = 0b0010;
+
+impl CoreModel {
+    /// Identify the core model from processor information.
+    ///
+    /// The core is identified by the family, `mcpuid[25:22]`, and class, `mcpuid[21:18]`,
+    /// fields of index word 0, as described for `mcpuid` in the XuanTie E902, E906, E907,
+    /// C906, C908, C910 and C920 user manuals:
+    ///
+    /// | Family         | Class    | Core |
+    /// |:---------------|:---------|:-----|
+    /// | `0b0001`, E    | `0b0000` | E902 |
+    /// | `0b0001`, E    | `0b0001` | E906 |
+    /// | `0b0001`, E    | `0b0010` | E907 |
+    /// | `0b0010`, C    | `0b0000` | C906 |
+    /// | `0b0010`, C    | `0b0001` | C908 |
+    /// | `0b0010`, C    | `0b0010` | C910 |
+    /// | `0b0010`, C    | `0b0011` | C920 |
+    /// | `0b0010`, C    | `0b0100` | C907 |
+    ///
+    /// The C907 class is not listed in these manuals; it is assumed to continue the C series
+    /// numbering. Any other family or class is reported as [`Unknown`](CoreModel::Unknown).
+    pub fn from_cpuid(cpuid: &Cpuid) -> CoreModel {
+        let (family, class) = (cpuid.family(), cpuid.class());
+        if !cpuid.is_thead() {
+            return CoreModel::Unknown { family, class };
+        }
+        match (family, class) {
+            (Some(FAMILY_E), Some(0b0000)) => CoreModel::E902,
+            (Some(FAMILY_E), Some(0b0001)) => CoreModel::E906,
+            (Some(FAMILY_E), Some(0b0010)) => CoreModel::E907,
+            (Some(FAMILY_C), Some(0b0000)) => CoreModel::C906,
+            (Some(FAMILY_C), Some(0b0001)) => CoreModel::C908,
+            (Some(FAMILY_C), Some(0b0010)) => CoreModel::C910,
+            (Some(FAMILY_C), Some(0b0011)) => CoreModel::C920,
+            (Some(FAMILY_C), Some(0b0100)) => CoreModel::C907,
+            _ => CoreModel::Unknown { family, class },
+        }
+    }
+    /// Base architecture of this core model
+    pub fn arch(&self) -> Option<Arch> {
+        match self {
+            CoreModel::E902 | CoreModel::E906 | CoreModel::E907 => Some(Arch::Rv32),
+            CoreModel::C906
+            | CoreModel::C907
+            | CoreModel::C908
+            | CoreModel::C910
+            | CoreModel::C920 => Some(Arch::Rv64),
+            CoreModel::Unknown { .. } => None,
+        }
+    }
+}
+
+/// Detect the model of the current core.
+#[inline]
+pub fn detect() -> CoreModel {
+    CoreModel::from_cpuid(&mcpuid::read())
+}
+
+/// Vector extension version
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum VectorVersion {
+    /// RISC-V vector extension draft 0.7.1
+    V0p7p1,
+    /// Ratified RISC-V vector extension 1.0
+    V1p0,
+}
+
+/// Optional XuanTie features implemented by a core
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Capabilities {
+    /// Core model these capabilities were determined for
+    pub model: CoreModel,
+    /// Core has a shared L2-cache, see `mccr2`
+    ///
+    /// Taken from the core model; not probed.
+    pub l2_cache: bool,
+    /// `sync.s` and `sync.is` broadcast to other cores of the cluster
+    ///
+    /// Taken from the core model; not probed.
+    pub sync_s_broadcast: bool,
+    /// CLINT supervisor extension, `mxstatus.clintee`
+    pub clintee: bool,
+    /// Extended MMU page attributes, `mxstatus.maee`
+    pub maee: bool,
+    /// Zkt extension enable bit, `mxstatus.zkte`
+    pub zkt: bool,
+    /// Coprocessor instructions enable bit, `mxstatus.copinstee`
+    pub copinstee: bool,
+    /// Packed SIMD (P) extension
+    pub p_extension: bool,
+    /// Vector extension, if implemented
+    pub vector: Option<VectorVersion>,
+    /// Cache ECC and error reporting through `mcer` and `mcer2`
+    ///
+    /// Taken from the core model; not probed.
+    pub ecc: bool,
+}
+
+impl Capabilities {
+    /// Capabilities every core of `model` implements, without probing any CSR.
+    ///
+    /// Features that are optional for `model`, such as the vector extension, are reported
+    /// as absent.
+    pub const fn for_model(model: CoreModel) -> Capabilities {
+        use CoreModel::*;
+        let c9xx = matches!(model, C910 | C920);
+        Capabilities {
+            model,
+            l2_cache: c9xx,
+            sync_s_broadcast: c9xx,
+            clintee: c9xx,
+            maee: matches!(model, C906 | C910 | C920),
+            zkt: matches!(model, C920),
+            copinstee: matches!(model, C920),
+            p_extension: matches!(model, E907),
+            vector: None,
+            ecc: c9xx,
+        }
+    }
+
+    /// Detect the current core and probe its CSRs for optional features.
+    ///
+    /// Must be called in machine mode with address translation off. Probed `mxstatus` bits
+    /// are set for a moment and restored afterwards; optional extensions are read from `misa`.
+    /// Fields documented as taken from the core model keep their [`for_model`](Self::for_model)
+    /// values.
+    ///
+    /// # Safety
+    ///
+    /// Must run in M mode, and no other code may change `mxstatus` meanwhile.
+    pub unsafe fn probe() -> Capabilities {
+        let mut caps = Capabilities::for_model(detect());
+        caps.clintee = probe_mxstatus(1 << 17);
+        caps.maee = probe_mxstatus(1 << 21);
+        caps.zkt = probe_mxstatus(1 << 23);
+        caps.copinstee = probe_mxstatus(1 << 24);
+        let misa = csr::read::<0x301>();
+        caps.p_extension = misa & (1 << (b'P' - b'A')) != 0;
+        if misa & (1 << (b'V' - b'A')) != 0 {
+            caps.vector = match caps.model {
+                CoreModel::C907 | CoreModel::C908 => Some(VectorVersion::V1p0),
+                _ => Some(VectorVersion::V0p7p1),
+            };
+        }
+        caps
+    }
+
+    /// Does the core support performance event `index` in `mhpmevent` registers
+    ///
+    /// Returns `false` for cores whose event catalogue is not compiled in.
+    pub fn supports_event(&self, index: usize) -> bool {
+        #[cfg(any(
+            xuantie_c920,
+            xuantie_c910,
+            xuantie_c906,
+            xuantie_c908,
+            xuantie_e907,
+            xuantie_e906
+        ))]
+        use crate::pmu::Event;
+        match self.model {
+            #[cfg(xuantie_c906)]
+            CoreModel::C906 => crate::pmu::c906::Event::ALL
+                .iter()
+                .any(|e| e.index() == index),
+            #[cfg(xuantie_c908)]
+            CoreModel::C908 => crate::pmu::c908::Event::ALL
+                .iter()
+                .any(|e| e.index() == index),
+            #[cfg(any(xuantie_c920, xuantie_c910))]
+            CoreModel::C910 | CoreModel::C920 => crate::pmu::c910::Event::ALL
+                .iter()
+                .any(|e| e.index() == index),
+            #[cfg(any(xuantie_e907, xuantie_e906))]
+            CoreModel::E906 | CoreModel::E907 => crate::pmu::e907::Event::ALL
+                .iter()
+                .any(|e| e.index() == index),
+            // no event catalogue for this core, or catalogue not compiled in
+            _ => {
+                let _ = index;
+                false
+            }
+        }
+    }
+}
+
+/// Check whether `mxstatus` bits in `mask` are implemented, leaving them as they were.
+unsafe fn probe_mxstatus(mask: usize) -> bool {
+    let old = csr::read::<0x7C0>();
+    if old & mask == mask {
+        return true;
+    }
+    csr::set::<0x7C0>(mask);
+    let supported = csr::read::<0x7C0>() & mask == mask;
+    csr::clear::<0x7C0>(mask & !old);
+    supported
+}
+
+#[cfg(all(test, not(any(target_arch = "riscv32", target_arch = "riscv64"))))]
+mod tests {
+    use super::CoreModel;
+    use crate::register::mcpuid::{Arch, Cpuid, THEAD_VENDOR_ID};
+
+    /// Index word 0 of `mcpuid`: architecture, family, class and model.
+    const fn word0(arch: u32, family: u32, class: u32, model: u32) -> u32 {
+        (arch << 26) | (family << 22) | (class << 18) | (model << 8)
+    }
+
+    fn detect(word: u32) -> CoreModel {
+        CoreModel::from_cpuid(&Cpuid::from_words(THEAD_VENDOR_ID, &[word]))
+    }
+
+    #[test]
+    fn from_cpuid() {
+        let cores = [
+            (word0(0b00, 0b0001, 0b0000, 0), CoreModel::E902),
+            (word0(0b00, 0b0001, 0b0001, 0), CoreModel::E906),
+            (word0(0b00, 0b0001, 0b0010, 0), CoreModel::E907),
+            (word0(0b10, 0b0010, 0b0000, 0), CoreModel::C906),
+            (word0(0b10, 0b0010, 0b0001, 0), CoreModel::C908),
+            (word0(0b10, 0b0010, 0b0010, 0), CoreModel::C910),
+            (word0(0b10, 0b0010, 0b0011, 0), CoreModel::C920),
+            (word0(0b10, 0b0010, 0b0100, 0), CoreModel::C907),
+        ];
+        for (word, model) in cores {
+            assert_eq!(detect(word), model, "mcpuid {word:#010x}");
+            let cpuid = Cpuid::from_words(THEAD_VENDOR_ID, &[word]);
+            assert_eq!(cpuid.arch(), model.arch(), "mcpuid {word:#010x}");
+        }
+        // the model field does not affect the core model
+        assert_eq!(detect(word0(0b10, 0b0010, 0b0010, 0x3FF)), CoreModel::C910);
+        assert_eq!(CoreModel::C910.arch(), Some(Arch::Rv64));
+    }
+
+    #[test]
+    fn from_cpuid_unknown() {
+        assert_eq!(
+            detect(word0(0b10, 0b0010, 0b1111, 0)),
+            CoreModel::Unknown {
+                family: Some(0b0010),
+                class: Some(0b1111)
+            }
+        );
+        assert_eq!(
+            detect(word0(0b10, 0b0011, 0b0000, 0)),
+            CoreModel::Unknown {
+                family: Some(0b0011),
+                class: Some(0b0000)
+            }
+        );
+        // another vendor using the same encoding
+        let cpuid = Cpuid::from_words(0x489, &[word0(0b10, 0b0010, 0b0010, 0)]);
+        assert_eq!(
+            CoreModel::from_cpuid(&cpuid),
+            CoreModel::Unknown {
+                family: Some(0b0010),
+                class: Some(0b0010)
+            }
+        );
+        // index word 0 missing
+        let cpuid = Cpuid::from_words(THEAD_VENDOR_ID, &[1 << 28]);
+        assert_eq!(
+            CoreModel::from_cpuid(&cpuid),
+            CoreModel::Unknown {
+                family: None,
+                class: None
+            }
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Programmable performance monitor unit.
+//!
+//! XuanTie cores count hardware events in `mhpmcounter3` to `mhpmcounter31`. Each counter
+//! is assigned an event through its `mhpmevent` register, and can be started or stopped
+//! independently through `mcountinhibit`. Events differ between cores; each core module
+//! below lists the events its core supports.
+//!
+//! Counters are always read as 64-bit values. On RV32 the low and high halves are read with
+//! the usual high-low-high loop, so a carry between the two halves never tears the result.
+//!
+//! # Example
+//!
+//! ```
+//! use xuantie_riscv::pmu::{self, c906, Counter};
+//! let counter = Counter::hpm(3).unwrap();
+//! unsafe {
+//!     pmu::stop(counter);
+//!     pmu::set_event(counter, c906::Event::L1DCacheReadMiss);
+//!     pmu::reset(counter);
+//!     pmu::start(counter);
+//! }
+//! // ... code to be measured ...
+//! unsafe { pmu::stop(counter) };
+//! let misses = pmu::read(counter);
+//! # assert_eq!(misses, 0);
+//! ```
+use crate::csr;
+
+/// Expands to a `match` on a counter index, calling `csr::$f::<{ $base + index }>` in each arm.
+macro_rules! dispatch {
+    ($index:expr, $base:literal, $f:ident $(, $arg:expr)*) => {
+        dispatch!(@arms $index, $base, $f, ($($arg),*),
+            0 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31)
+    };
+    (@arms $index:expr, $base:literal, $f:ident, $args:tt, $($n:literal)*) => {
+        match $index {
+            $($n => csr::$f::<{ $base + $n }> $args,)*
+            _ => unreachable!(),
+        }
+    };
+}
+
+/// Defines the `Event` enum of a core event catalogue.
+macro_rules! event_catalogue {
+    (
+        $(#[$attr:meta])*
+        pub enum Event {
+            $($(#[$event_attr:meta])* $event:ident = $index:literal,)+
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[repr(usize)]
+        pub enum Event {
+            $($(#[$event_attr])* $event = $index,)+
+        }
+
+        impl Event {
+            /// Every event supported by this core.
+            pub const ALL: &'static [Event] = &[$(Event::$event,)+];
+        }
+
+        impl super::Event for Event {
+            #[inline]
+            fn index(self) -> usize {
+                self as usize
+            }
+        }
+    };
+}
+
+#[cfg(xuantie_c906)]
+pub mod c906;
+#[cfg(xuantie_c908)]
+pub mod c908;
+#[cfg(any(xuantie_c920, xuantie_c910))]
+pub mod c910;
+#[cfg(any(xuantie_e907, xuantie_e906))]
+pub mod e907;
+#[cfg(all(
+    any(xuantie_c920, xuantie_c910, xuantie_c906),
+    target_pointer_width = "64"
+))]
+pub mod profile;
+
+/// Performance event that can be assigned to a hardware performance monitor counter.
+pub trait Event: Copy {
+    /// Event index written into `mhpmevent`.
+    fn index(self) -> usize;
+}
+
+/// Raw event index, for events not listed in the core event catalogues.
+impl Event for usize {
+    #[inline]
+    fn index(self) -> usize {
+        self
+    }
+}
+
+/// Performance counter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Counter(u8);
+
+impl Counter {
+    /// Cycle counter, `mcycle`.
+    pub const CYCLE: Counter = Counter(0);
+    /// Instructions-retired counter, `minstret`.
+    pub const INSTRET: Counter = Counter(2);
+
+    /// Hardware performance monitor counter `mhpmcounter{index}`.
+    ///
+    /// Returns `None` if `index` is not in `3..=31`.
+    #[inline]
+    pub const fn hpm(index: usize) -> Option<Counter> {
+        match index {
+            3..=31 => Some(Counter(index as u8)),
+            _ => None,
+        }
+    }
+    /// Index of this counter; its bit in `mcountinhibit` and the counter control registers.
+    #[inline]
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+    /// Whether this counter is a programmable hardware performance monitor counter.
+    #[inline]
+    pub const fn is_hpm(self) -> bool {
+        self.0 >= 3
+    }
+}
+
+/// Assign `event` to hardware performance monitor counter `counter`.
+///
+/// # Panics
+///
+/// Panics if `counter` is the cycle or instructions-retired counter.
+///
+/// # Safety
+///
+/// Must run in M mode.
+#[inline]
+pub unsafe fn set_event<E: Event>(counter: Counter, event: E) {
+    assert!(counter.is_hpm());
+    dispatch!(counter.0, 0x320, write, event.index())
+}
+
+/// Event index currently assigned to hardware performance monitor counter `counter`.
+///
+/// # Panics
+///
+/// Panics if `counter` is the cycle or instructions-retired counter.
+#[inline]
+pub fn event(counter: Counter) -> usize {
+    assert!(counter.is_hpm());
+    dispatch!(counter.0, 0x320, read)
+}
+
+/// Start counting on `counter`.
+///
+/// # Safety
+///
+/// Must run in M mode.
+#[inline]
+pub unsafe fn start(counter: Counter) {
+    csr::clear::<0x320>(1 << counter.index());
+}
+
+/// Stop counting on `counter`.
+///
+/// # Safety
+///
+/// Must run in M mode.
+#[inline]
+pub unsafe fn stop(counter: Counter) {
+    csr::set::<0x320>(1 << counter.index());
+}
+
+/// Whether `counter` is currently counting.
+#[inline]
+pub fn is_running(counter: Counter) -> bool {
+    csr::read::<0x320>() & (1 << counter.index()) == 0
+}
+
+/// Reset `counter` to zero.
+///
+/// # Safety
+///
+/// Must run in M mode, or in S mode if counter writes are delegated with `mcounterwen`.
+#[inline]
+pub unsafe fn reset(counter: Counter) {
+    write(counter, 0);
+}
+
+/// Read the 64-bit value of `counter`.
+#[inline]
+pub fn read(counter: Counter) -> u64 {
+    match () {
+        #[cfg(target_pointer_width = "32")]
+        () => loop {
+            let hi = dispatch!(counter.0, 0xB80, read);
+            let lo = dispatch!(counter.0, 0xB00, read);
+            if hi == dispatch!(counter.0, 0xB80, read) {
+                break ((hi as u64) << 32) | lo as u64;
+            }
+        },
+        #[cfg(not(target_pointer_width = "32"))]
+        () => dispatch!(counter.0, 0xB00, read) as u64,
+    }
+}
+
+/// Write the 64-bit value of `counter`.
+///
+/// On RV32 the low half is cleared first, so no carry into the high half happens
+/// while the two halves are written.
+///
+/// # Safety
+///
+/// Must run in M mode, or in S mode if counter writes are delegated with `mcounterwen`.
+#[inline]
+pub unsafe fn write(counter: Counter, value: u64) {
+    match () {
+        #[cfg(target_pointer_width = "32")]
+        () => {
+            dispatch!(counter.0, 0xB00, write, 0);
+            dispatch!(counter.0, 0xB80, write, (value >> 32) as usize);
+            dispatch!(counter.0, 0xB00, write, value as usize);
+        }
+        #[cfg(not(target_pointer_width = "32"))]
+        () => dispatch!(counter.0, 0xB00, write, value as usize),
+    }
+}
+
+/// Counters sampled by [`measure`] in addition to cycles and instructions retired.
+///
+/// A `None` entry is not sampled, and the corresponding field of the [`Report`] is `None`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Counters {
+    /// Counter counting L1 I-cache misses
+    pub l1_i_cache_miss: Option<Counter>,
+    /// Counter counting L1 D-cache misses
+    pub l1_d_cache_miss: Option<Counter>,
+    /// Counter counting TLB misses
+    pub tlb_miss: Option<Counter>,
+    /// Counter counting branch mispredicts
+    pub branch_miss: Option<Counter>,
+}
+
+impl Counters {
+    /// Sample cycles and instructions retired only.
+    pub const NONE: Counters = Counters {
+        l1_i_cache_miss: None,
+        l1_d_cache_miss: None,
+        tlb_miss: None,
+        branch_miss: None,
+    };
+    /// Default event assignment of the C906 and C910 counters.
+    ///
+    /// L1 I-cache misses on `mhpmcounter4`, L1 D-cache read misses on `mhpmcounter15`,
+    /// jTLB misses on `mhpmcounter7` and conditional branch mispredicts on `mhpmcounter8`.
+    pub const DEFAULT: Counters = Counters {
+        l1_i_cache_miss: Counter::hpm(4),
+        l1_d_cache_miss: Counter::hpm(15),
+        tlb_miss: Counter::hpm(7),
+        branch_miss: Counter::hpm(8),
+    };
+}
+
+/// Counter deltas over a region measured by [`measure`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    /// Cycles elapsed
+    pub cycles: u64,
+    /// Instructions retired
+    pub instructions: u64,
+    /// L1 I-cache misses
+    pub l1_i_cache_misses: Option<u64>,
+    /// L1 D-cache misses
+    pub l1_d_cache_misses: Option<u64>,
+    /// TLB misses
+    pub tlb_misses: Option<u64>,
+    /// Branch mispredicts
+    pub branch_misses: Option<u64>,
+}
+
+/// Run `f` and report how much each of `counters` advanced meanwhile.
+///
+/// Counters must already be assigned their events and running, see [`set_event`] and
+/// [`start`]. Deltas are computed modulo 2<sup>64</sup>, so a counter wrapping around during
+/// the region is still reported correctly. The cycle counter is read last before and first
+/// after `f`, keeping the measurement overhead out of the cycle count as far as possible.
+///
+/// # Example
+///
+/// ```
+/// use xuantie_riscv::pmu::{measure, Counters};
+/// let (sum, report) = measure(&Counters::DEFAULT, || (1..=100u32).sum::<u32>());
+/// assert_eq!(sum, 5050);
+/// # assert_eq!(report.cycles, 0);
+/// let misses_per_instruction = report.l1_d_cache_misses.unwrap() as f32 / report.instructions as f32;
+/// ```
+#[inline]
+pub fn measure<R>(counters: &Counters, f: impl FnOnce() -> R) -> (R, Report) {
+    let read_all = |counters: &Counters| {
+        [
+            counters.l1_i_cache_miss.map(read),
+            counters.l1_d_cache_miss.map(read),
+            counters.tlb_miss.map(read),
+            counters.branch_miss.map(read),
+        ]
+    };
+    let start = read_all(counters);
+    let instret_start = read(Counter::INSTRET);
+    let cycle_start = read(Counter::CYCLE);
+    let result = f();
+    let cycle_end = read(Counter::CYCLE);
+    let instret_end = read(Counter::INSTRET);
+    let end = read_all(counters);
+    let delta = |i: usize| Some(end[i]?.wrapping_sub(start[i]?));
+    let report = Report {
+        cycles: cycle_end.wrapping_sub(cycle_start),
+        instructions: instret_end.wrapping_sub(instret_start),
+        l1_i_cache_misses: delta(0),
+        l1_d_cache_misses: delta(1),
+        tlb_misses: delta(2),
+        branch_misses: delta(3),
+    };
+    (result, report)
+}
@@ -0,0 +1,31 @@
+//! Performance events of the XuanTie C906 core.
+
+event_catalogue! {
+    /// C906 performance event
+    pub enum Event {
+        /// L1 I-cache access
+        L1ICacheAccess = 0x01,
+        /// L1 I-cache miss
+        L1ICacheMiss = 0x02,
+        /// I-uTLB miss
+        IUtlbMiss = 0x03,
+        /// D-uTLB miss
+        DUtlbMiss = 0x04,
+        /// jTLB miss
+        JtlbMiss = 0x05,
+        /// Conditional branch mispredict
+        ConditionalBranchMispredict = 0x06,
+        /// Conditional branch instruction
+        ConditionalBranchInstruction = 0x07,
+        /// Store instruction
+        StoreInstruction = 0x0B,
+        /// L1 D-cache read access
+        L1DCacheReadAccess = 0x0C,
+        /// L1 D-cache read miss
+        L1DCacheReadMiss = 0x0D,
+        /// L1 D-cache write access
+        L1DCacheWriteAccess = 0x0E,
+        /// L1 D-cache write miss
+        L1DCacheWriteMiss = 0x0F,
+    }
+}
@@ -0,0 +1,91 @@
+//! Performance events of the XuanTie C910 and C920 cores.
+
+event_catalogue! {
+    /// C910 and C920 performance event
+    pub enum Event {
+        /// L1 I-cache access
+        L1ICacheAccess = 0x01,
+        /// L1 I-cache miss
+        L1ICacheMiss = 0x02,
+        /// I-uTLB miss
+        IUtlbMiss = 0x03,
+        /// D-uTLB miss
+        DUtlbMiss = 0x04,
+        /// jTLB miss
+        JtlbMiss = 0x05,
+        /// Conditional branch mispredict
+        ConditionalBranchMispredict = 0x06,
+        /// Conditional branch instruction
+        ConditionalBranchInstruction = 0x07,
+        /// Indirect branch mispredict
+        IndirectBranchMispredict = 0x08,
+        /// Indirect branch instruction
+        IndirectBranchInstruction = 0x09,
+        /// LSU speculation fail
+        LsuSpecFail = 0x0A,
+        /// Store instruction
+        StoreInstruction = 0x0B,
+        /// L1 D-cache read access
+        L1DCacheReadAccess = 0x0C,
+        /// L1 D-cache read miss
+        L1DCacheReadMiss = 0x0D,
+        /// L1 D-cache write access
+        L1DCacheWriteAccess = 0x0E,
+        /// L1 D-cache write miss
+        L1DCacheWriteMiss = 0x0F,
+        /// L2 cache read access
+        L2CacheReadAccess = 0x10,
+        /// L2 cache read miss
+        L2CacheReadMiss = 0x11,
+        /// L2 cache write access
+        L2CacheWriteAccess = 0x12,
+        /// L2 cache write miss
+        L2CacheWriteMiss = 0x13,
+        /// Rename-to-dispatch launch fail
+        RfLaunchFail = 0x14,
+        /// Rename-to-dispatch register launch fail
+        RfRegLaunchFail = 0x15,
+        /// Instruction passing rename-to-dispatch
+        RfInstruction = 0x16,
+        /// LSU stall caused by crossing a 4 KiB boundary
+        LsuCross4kStall = 0x17,
+        /// LSU stall for other reasons
+        LsuOtherStall = 0x18,
+        /// LSU store queue discard
+        LsuSqDiscard = 0x19,
+        /// LSU store queue data discard
+        LsuSqDataDiscard = 0x1A,
+        /// Branch target mispredict
+        BranchTargetMispredict = 0x1B,
+        /// Branch target instruction
+        BranchTargetInstruction = 0x1C,
+        /// ALU instruction
+        AluInstruction = 0x1D,
+        /// Load or store instruction
+        LdstInstruction = 0x1E,
+        /// Vector SIMD instruction
+        VectorSimdInstruction = 0x1F,
+        /// CSR instruction
+        CsrInstruction = 0x20,
+        /// Synchronization instruction
+        SyncInstruction = 0x21,
+        /// Unaligned load or store access
+        LdstUnalignedAccess = 0x22,
+        /// Number of interrupts taken
+        InterruptNumber = 0x23,
+        /// Cycles with interrupts disabled
+        InterruptOffCycle = 0x24,
+        /// Environment call
+        EnvironmentCall = 0x25,
+        /// Long jump
+        LongJump = 0x26,
+        /// Stalled cycles in the frontend
+        StalledCyclesFrontend = 0x27,
+        /// Stalled cycles in the backend
+        StalledCyclesBackend = 0x28,
+        /// Stalled cycles on synchronization instructions
+        SyncStall = 0x29,
+        /// Floating point instruction
+        FloatPointInstruction = 0x2A,
+    }
+}
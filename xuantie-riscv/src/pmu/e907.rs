@@ -0,0 +1,45 @@
+//! Performance events of the XuanTie E907 and E906 cores.
+
+event_catalogue! {
+    /// E907 and E906 performance event
+    pub enum Event {
+        /// L1 I-cache access
+        L1ICacheAccess = 0x01,
+        /// L1 I-cache miss
+        L1ICacheMiss = 0x02,
+        /// Conditional branch mispredict
+        ConditionalBranchMispredict = 0x06,
+        /// Conditional branch instruction
+        ConditionalBranchInstruction = 0x07,
+        /// Store instruction
+        StoreInstruction = 0x0B,
+        /// L1 D-cache read access
+        L1DCacheReadAccess = 0x0C,
+        /// L1 D-cache read miss
+        L1DCacheReadMiss = 0x0D,
+        /// L1 D-cache write access
+        L1DCacheWriteAccess = 0x0E,
+        /// L1 D-cache write miss
+        L1DCacheWriteMiss = 0x0F,
+        /// ALU instruction
+        AluInstruction = 0x1D,
+        /// Load or store instruction
+        LdstInstruction = 0x1E,
+        /// CSR instruction
+        CsrInstruction = 0x20,
+        /// Synchronization instruction
+        SyncInstruction = 0x21,
+        /// Unaligned load or store access
+        LdstUnalignedAccess = 0x22,
+        /// Number of interrupts taken
+        InterruptNumber = 0x23,
+        /// Cycles with interrupts disabled
+        InterruptOffCycle = 0x24,
+        /// Environment call
+        EnvironmentCall = 0x25,
+        /// Long jump
+        LongJump = 0x26,
+        /// Floating point instruction
+        FloatPointInstruction = 0x2A,
+    }
+}
@@ -0,0 +1,247 @@
+//! Sampling profiler driven by counter overflow interrupts.
+//!
+//! A [`Profiler`] presets a performance counter so that it overflows after every `period`
+//! events. On each overflow the XuanTie PMU overflow interrupt handler calls
+//! [`Profiler::handle_overflow`], which records the interrupted program counter `mepc` into
+//! a fixed-size ring buffer and re-arms the counter. Samples are drained with
+//! [`Profiler::pop`], or serialized with [`Profiler::export`] for a host tool to build a
+//! program counter histogram.
+//!
+//! The ring buffer has a single producer, the overflow interrupt handler, and a single
+//! consumer. It only uses atomic loads and stores, so it also works on cores without the
+//! atomic extension. Samples taken while the buffer is full are counted as dropped.
+//!
+//! # Example
+//!
+//! ```
+//! # use xuantie_riscv::sim;
+//! use xuantie_riscv::pmu::{self, c906, profile::Profiler, Counter};
+//! // sample once every 1000 L1 D-cache read misses
+//! static PROFILER: Profiler<256> = Profiler::new(Counter::hpm(3).unwrap(), 1000);
+//!
+//! #[unsafe(no_mangle)]
+//! extern "C" fn THeadPmuOverflow() {
+//!     unsafe { PROFILER.handle_overflow() };
+//! }
+//!
+//! unsafe {
+//!     pmu::set_event(Counter::hpm(3).unwrap(), c906::Event::L1DCacheReadMiss);
+//!     PROFILER.start();
+//! }
+//! # sim::write(0x341, 0x8020_1234);
+//! # sim::write(0x7CB, 1 << 3);
+//! # THeadPmuOverflow();
+//! // ... run the workload, then stop and collect samples
+//! unsafe { PROFILER.stop() };
+//! let mut buf = [0u8; 1024];
+//! let len = PROFILER.export(&mut buf);
+//! # assert_eq!(len, 24 + core::mem::size_of::<usize>());
+//! # assert!(PROFILER.is_empty());
+//! ```
+//!
+//! # Export format
+//!
+//! All fields are little endian.
+//!
+//! | Offset | Size | Field |
+//! |:-------|:-----|:------|
+//! | 0      | 4    | Magic `b"XTPF"` |
+//! | 4      | 1    | Format version, currently 1 |
+//! | 5      | 1    | Sample width in bytes, 4 on RV32 and 8 on RV64 |
+//! | 6      | 2    | Counter index |
+//! | 8      | 8    | Sampling period in events |
+//! | 16     | 4    | Number of samples that follow |
+//! | 20     | 4    | Number of samples dropped so far, saturating |
+//! | 24     | -    | Sampled `mepc` values, each of the sample width |
+use super::Counter;
+use crate::{
+    csr,
+    register::{mcounterinten, mcounterof, Register},
+};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Magic bytes at the start of an exported profile.
+pub const MAGIC: [u8; 4] = *b"XTPF";
+/// Version of the export format.
+pub const VERSION: u8 = 1;
+/// Size of the export header in bytes.
+pub const HEADER_LEN: usize = 24;
+
+/// Sampling profiler holding up to `N` samples.
+///
+/// `N` must be a power of two, so that buffer indices stay consistent when the wrapping
+/// head and tail counters overflow.
+pub struct Profiler<const N: usize> {
+    counter: Counter,
+    period: u64,
+    samples: [AtomicUsize; N],
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    dropped: AtomicUsize,
+}
+
+impl<const N: usize> Profiler<N> {
+    /// Create a profiler sampling `counter` once every `period` events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero or `N` is not a power of two. For a profiler in a
+    /// `static` this is reported at compile time.
+    #[inline]
+    pub const fn new(counter: Counter, period: u64) -> Self {
+        assert!(period != 0 && N.is_power_of_two());
+        Self {
+            counter,
+            period,
+            samples: [const { AtomicUsize::new(0) }; N],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            dropped: AtomicUsize::new(0),
+        }
+    }
+    /// Sampled counter
+    #[inline]
+    pub const fn counter(&self) -> Counter {
+        self.counter
+    }
+    /// Sampling period in events
+    #[inline]
+    pub const fn period(&self) -> u64 {
+        self.period
+    }
+    /// Arm the counter and enable its overflow interrupt.
+    ///
+    /// The event to sample must already be assigned to the counter with
+    /// [`pmu::set_event`](super::set_event). The PMU overflow interrupt must be enabled in
+    /// `mie` for samples to be taken.
+    ///
+    /// # Safety
+    ///
+    /// Must run in M mode. No other code may use the counter until [`stop`](Self::stop).
+    #[inline]
+    pub unsafe fn start(&self) {
+        super::stop(self.counter);
+        self.arm();
+        mcounterof::modify(|r| with_counter(r, self.counter, false));
+        mcounterinten::modify(|r| with_counter(r, self.counter, true));
+        super::start(self.counter);
+    }
+    /// Stop the counter and disable its overflow interrupt.
+    ///
+    /// # Safety
+    ///
+    /// Must run in M mode.
+    #[inline]
+    pub unsafe fn stop(&self) {
+        super::stop(self.counter);
+        mcounterinten::modify(|r| with_counter(r, self.counter, false));
+    }
+    /// Handle an overflow of the sampled counter.
+    ///
+    /// Call this from the `THeadPmuOverflow` interrupt handler. If the sampled counter has
+    /// overflowed, records `mepc`, re-arms the counter, acknowledges the overflow and
+    /// returns `true`. Otherwise returns `false`, leaving other counters' overflows pending.
+    ///
+    /// # Safety
+    ///
+    /// Must run in M mode, from the overflow interrupt handler of the current hart.
+    #[inline]
+    pub unsafe fn handle_overflow(&self) -> bool {
+        if !has_counter(mcounterof::read(), self.counter) {
+            return false;
+        }
+        self.push(csr::read::<0x341>());
+        self.arm();
+        mcounterof::modify(|r| with_counter(r, self.counter, false));
+        true
+    }
+    /// Take the oldest sample out of the buffer.
+    #[inline]
+    pub fn pop(&self) -> Option<usize> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        if tail == self.head.load(Ordering::Acquire) {
+            return None;
+        }
+        let sample = self.samples[tail % N].load(Ordering::Relaxed);
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Some(sample)
+    }
+    /// Number of samples in the buffer
+    #[inline]
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        head.wrapping_sub(self.tail.load(Ordering::Acquire))
+    }
+    /// Is the buffer empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Number of samples dropped because the buffer was full
+    #[inline]
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+    /// Drain samples into `buf` in the export format and return the number of bytes written.
+    ///
+    /// As many samples as fit into `buf` are moved out of the buffer; the rest stay for the
+    /// next export. Returns zero if `buf` cannot hold the header.
+    pub fn export(&self, buf: &mut [u8]) -> usize {
+        const WIDTH: usize = core::mem::size_of::<usize>();
+        if buf.len() < HEADER_LEN {
+            return 0;
+        }
+        let capacity = ((buf.len() - HEADER_LEN) / WIDTH).min(u32::MAX as usize);
+        let mut count = 0;
+        while count < capacity {
+            let Some(sample) = self.pop() else { break };
+            let offset = HEADER_LEN + count * WIDTH;
+            buf[offset..offset + WIDTH].copy_from_slice(&sample.to_le_bytes());
+            count += 1;
+        }
+        let dropped = self.dropped().min(u32::MAX as usize) as u32;
+        buf[0..4].copy_from_slice(&MAGIC);
+        buf[4] = VERSION;
+        buf[5] = WIDTH as u8;
+        buf[6..8].copy_from_slice(&(self.counter.index() as u16).to_le_bytes());
+        buf[8..16].copy_from_slice(&self.period.to_le_bytes());
+        buf[16..20].copy_from_slice(&(count as u32).to_le_bytes());
+        buf[20..24].copy_from_slice(&dropped.to_le_bytes());
+        HEADER_LEN + count * WIDTH
+    }
+    /// Preset the counter so that it overflows after `period` more events.
+    #[inline]
+    unsafe fn arm(&self) {
+        super::write(self.counter, self.period.wrapping_neg());
+    }
+    /// Append a sample, or count it as dropped if the buffer is full.
+    #[inline]
+    fn push(&self, sample: usize) {
+        let head = self.head.load(Ordering::Relaxed);
+        if head.wrapping_sub(self.tail.load(Ordering::Acquire)) >= N {
+            let dropped = self.dropped.load(Ordering::Relaxed);
+            self.dropped
+                .store(dropped.saturating_add(1), Ordering::Relaxed);
+            return;
+        }
+        self.samples[head % N].store(sample, Ordering::Relaxed);
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+    }
+}
+
+/// Bit of `counter` in a counter control register
+#[inline]
+fn has_counter<R: Register>(r: R, counter: Counter) -> bool {
+    r.bits() & (1 << counter.index()) != 0
+}
+
+/// Set or clear the bit of `counter` in a counter control register
+#[inline]
+fn with_counter<R: Register>(r: R, counter: Counter, value: bool) -> R {
+    let mask = 1 << counter.index();
+    R::from_bits(if value {
+        r.bits() | mask
+    } else {
+        r.bits() & !mask
+    })
+}
@@ -1,37 +1,72 @@
 //! XuanTie extended CSRs.
+//!
+//! Registers that can be written as a whole implement the [`Register`] trait. Their modules
+//! also provide `read`, `write` and `modify` functions, and the register structures provide
+//! builder-style `with_*` setters for each writable field, so that a whole register can be
+//! configured in one CSR write.
+//!
+//! Multi-bit field setters such as `mccr2::set_dltncy` and the `modify` functions only touch
+//! the bits they change, using a `csrs` and `csrc` pair. A trap handler that changes other bits
+//! of the same CSR between the two instructions does not have its change lost.
+//!
+//! ```
+//! use xuantie_riscv::register::mhcr;
+//! // enable I-cache and D-cache in write-back mode with one CSR write
+//! unsafe { mhcr::modify(|r| r.with_ie(true).with_de(true).with_wb(true)) };
+//! ```
 
 // Extended state registers for performance cores
 pub mod mxstatus; // 0x7C0 
 pub mod mhcr; // 0x7C1
 pub mod mcor; // 0x7C2
+#[cfg(any(xuantie_c920, xuantie_c910))]
 pub mod mccr2; // 0x7C3, mccr2h 0x7CB RV32
+#[cfg(any(xuantie_c920, xuantie_c910))]
 pub mod mcer2; // 0x7C4, mcer2h 0x7FF RV32
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
 pub mod mhint; // 0x7C5, mhint2h 0x7CA RV32
 pub mod mrmr; // 0x7C6
 pub mod mrvbr; // 0x7C7
 pub mod mcer; // 0x7C8
-// pub mod mcounterwen; // 0x7C9
-// pub mod mcounterinten; // 0x7CA
-// pub mod mcounterof; // 0x7CB
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906))]
+pub mod mcounterwen; // 0x7C9
+#[cfg(all(
+    any(xuantie_c920, xuantie_c910, xuantie_c906),
+    target_pointer_width = "64"
+))]
+pub mod mcounterinten; // 0x7CA, RV64 only; mhint2h on RV32
+#[cfg(all(
+    any(xuantie_c920, xuantie_c910, xuantie_c906),
+    target_pointer_width = "64"
+))]
+pub mod mcounterof; // 0x7CB, RV64 only; mccr2h on RV32
 
 // Extended supervisor state registers
 pub mod sxstatus; // 0x5C0 
 pub mod shcr; // 0x5C1
+#[cfg(any(xuantie_c920, xuantie_c910))]
 pub mod scer2; // 0x5C2, scer2h 0x5D4 RV32
 pub mod scer; // 0x5C3, scerh 0x5D3 RV32
-// pub mod scounterinten; // 0x5C4
-// pub mod scounterof; // 0x5C5
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906))]
+pub mod scounterinten; // 0x5C4
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906))]
+pub mod scounterof; // 0x5C5
 
 // Extended supervisor virtual memory registers
 // pub mod smir; // 0x9C0
+#[cfg(target_pointer_width = "64")]
 pub mod smel; // 0x9C1
 pub mod smeh; // 0x9C2
 // pub mod smcir; // 0x9C3
 
 // Extended state registers for embedded cores
+#[cfg(any(xuantie_e907, xuantie_e906, xuantie_e902))]
 pub mod mraddr; // 0x7E0
+#[cfg(any(xuantie_e907, xuantie_e906, xuantie_e902))]
 pub mod mexstatus; // 0x7E1
+#[cfg(any(xuantie_e907, xuantie_e906, xuantie_e902))]
 pub mod mnmicause; // 0x7E2
+#[cfg(any(xuantie_e907, xuantie_e906, xuantie_e902))]
 pub mod mnmipc; // 0x7E3
 
 // Extended float point registers
@@ -39,17 +74,52 @@ pub mod fxcr; // 0x800
 
 // Performance counter
 pub mod mhpmcounter;
-// pub mod shpmcounter; // 0x5E3..=0x5FF
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906))]
+pub mod shpmcounter; // 0x5E3..=0x5FF
 
 // Cache direct access registers
 pub mod mcins; // 0x7D2
 pub mod mcindex; // 0x7D3, mcindexh 0x7DA RV32
-pub mod mcdata; // mcdata0 0x7D4, mcdata1 0x7D5, mcdata0h 0x7DB RV32, mcdata1h 0x7DB? RV32
+pub mod mcdata; // mcdata0 0x7D4, mcdata1 0x7D5, mcdata0h 0x7DB RV32, mcdata1h 0x7DC RV32
 pub mod meicr; // 0x7D6
 pub mod meicr2; // 0x7D7
-// pub mod mbeaddr; // 0x7D8
+#[cfg(any(xuantie_c920, xuantie_c910))]
+pub mod mbeaddr; // 0x7D8
 
 // Processor identification registers
 pub mod mcpuid; // 0xFC0
 pub mod mapbaddr; // 0xFC1
 // pub mod mapbaddr2; // 0xFC3
+
+/// Reserved encoding read from a register field.
+///
+/// Field decoders return this error instead of panicking when a field holds an encoding that
+/// is reserved, or not known to this crate. It carries the raw value of the field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Reserved(pub usize);
+
+/// Common interface of XuanTie CSRs that can be read and written as a whole.
+pub trait Register: Copy {
+    /// Convert bit representation into register value, keeping all the bits.
+    fn from_bits(bits: usize) -> Self;
+    /// Returns the raw bits of the register value.
+    fn bits(&self) -> usize;
+    /// Reads the CSR.
+    fn read() -> Self;
+    /// Writes `value` into the CSR.
+    ///
+    /// # Safety
+    ///
+    /// Every field of the CSR is replaced; the caller must ensure the new value is valid
+    /// for this core and that code running afterwards does not rely on the old one.
+    unsafe fn write(value: Self);
+    /// Reads the CSR, applies `f` to its value and writes the result back.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Register::write`]: the fields changed by `f` must be valid for this core.
+    #[inline]
+    unsafe fn modify<F: FnOnce(Self) -> Self>(f: F) {
+        Self::write(f(Self::read()))
+    }
+}
@@ -1,7 +1,7 @@
 //! fxcr, user extended float pointer control register
 
 use bit_field::BitField;
-use core::arch::asm;
+use crate::csr;
 
 /// Floating-point control and status register
 #[derive(Clone, Copy, Debug)]
@@ -39,8 +39,15 @@ pub enum RoundingMode {
 }
 
 impl Fxcr {
-    /// Returns the contents of the register as raw bits
-    pub fn bits(&self) -> usize {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
         self.bits
     }
 
@@ -68,9 +75,31 @@ impl Fxcr {
             _ => RoundingMode::Invalid,
         }
     }
+
+    /// Set accrued exception flags
+    #[inline]
+    pub fn with_flags(mut self, flags: Flags) -> Self {
+        self.bits.set_bits(0..=5, flags.bits());
+        self
+    }
+
+    /// Set output QNaN mode
+    #[inline]
+    pub fn with_dqnan(mut self, dqnan: bool) -> Self {
+        self.bits.set_bit(23, dqnan);
+        self
+    }
+
+    /// Set rounding mode
+    #[inline]
+    pub fn with_rm(mut self, rm: RoundingMode) -> Self {
+        self.bits.set_bits(24..=26, rm as usize);
+        self
+    }
 }
 
 read_csr_as!(Fxcr, 0x800);
+write_csr_as!(Fxcr, 0x800);
 
 set_clear_csr! {
     /// Output QNaN mode
@@ -78,18 +107,30 @@ set_clear_csr! {
 }
 
 /// Insert float point flags, setting corresponding bits to one.
+///
+/// # Safety
+///
+/// Changes floating point behavior of code running afterwards.
 #[inline]
 pub unsafe fn insert_flags(other: Flags) {
-    asm!("csrs 0x800, {}", in(reg) other.bits())
+    csr::set::<0x800>(other.bits());
 }
 
 /// Remove float point flags, setting corresponding bits to zero
+///
+/// # Safety
+///
+/// Changes floating point behavior of code running afterwards.
 #[inline]
 pub unsafe fn remove_flags(other: Flags) {
-    asm!("csrc 0x800, {}", in(reg) other.bits())
+    csr::clear::<0x800>(other.bits());
 }
 
 /// Inserts or removes float point flags depending on the passed value
+///
+/// # Safety
+///
+/// Changes floating point behavior of code running afterwards.
 #[inline]
 pub unsafe fn set_flags(other: Flags, value: bool) {
     if value {
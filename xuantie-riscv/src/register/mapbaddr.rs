@@ -1,10 +1,8 @@
 //! mapbaddr, machine APB base address register
-use core::arch::asm;
+use crate::csr;
 
 /// Get APB peripheral base address
 #[inline]
 pub fn read() -> usize {
-    let ans: usize;
-    unsafe { asm!("csrr {}, 0xFC1", out(reg) ans) };
-    ans
+    csr::read::<0xFC1>()
 }
@@ -0,0 +1,15 @@
+//! mbeaddr, machine bus error address register
+//!
+//! When a bus error is reported through the imprecise bus error interrupt, this register
+//! holds the physical address of the access that caused it.
+//!
+//! # Platform support
+//!
+//! This register is supported on Xuantie C920 and C910 cores.
+use crate::csr;
+
+/// Get physical address of the access that caused the last bus error
+#[inline]
+pub fn read() -> usize {
+    csr::read::<0x7D8>()
+}
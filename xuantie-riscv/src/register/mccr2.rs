@@ -1,6 +1,11 @@
 //! mccr2, machine L2-cache control register
+//!
+//! # Platform support
+//!
+//! This register is supported on Xuantie C920 and C910 cores.
 use bit_field::BitField;
-use core::arch::asm;
+use crate::csr;
+use super::Reserved;
 
 /// mccr2 register
 #[derive(Clone, Copy, Debug)]
@@ -42,6 +47,16 @@ pub enum IPRF {
 }
 
 impl Mccr2 {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
     /// Refill enable
     #[inline]
     pub fn rfe(&self) -> bool {
@@ -79,14 +94,14 @@ impl Mccr2 {
     }
     /// L2-cache tag ram visit latency configuration
     #[inline]
-    pub fn tltncy(&self) -> TLTNCY {
+    pub fn tltncy(&self) -> Result<TLTNCY, Reserved> {
         match self.bits.get_bits(22..=24) {
-            0 => TLTNCY::Cycle1,
-            1 => TLTNCY::Cycle2,
-            2 => TLTNCY::Cycle3,
-            3 => TLTNCY::Cycle4,
-            4 => TLTNCY::Cycle5,
-            _ => unreachable!(),
+            0 => Ok(TLTNCY::Cycle1),
+            1 => Ok(TLTNCY::Cycle2),
+            2 => Ok(TLTNCY::Cycle3),
+            3 => Ok(TLTNCY::Cycle4),
+            4 => Ok(TLTNCY::Cycle5),
+            raw => Err(Reserved(raw)),
         }
     }
     /// L2-cache tag ram setup latency enable
@@ -110,10 +125,95 @@ impl Mccr2 {
     pub fn tprf(&self) -> bool {
         self.bits.get_bit(31)
     }
-    // TODO PAE bit
+    /// Set refill enable
+    #[inline]
+    pub fn with_rfe(mut self, rfe: bool) -> Self {
+        self.bits.set_bit(0, rfe);
+        self
+    }
+    /// Set error correction enable
+    #[inline]
+    pub fn with_eccen(mut self, eccen: bool) -> Self {
+        self.bits.set_bit(1, eccen);
+        self
+    }
+    /// Set L2-cache enable
+    #[inline]
+    pub fn with_l2en(mut self, l2en: bool) -> Self {
+        self.bits.set_bit(3, l2en);
+        self
+    }
+    /// Set L2-cache data ram visit latency configuration
+    #[inline]
+    pub fn with_dltncy(mut self, dltncy: DLTNCY) -> Self {
+        self.bits.set_bits(16..=18, dltncy as usize);
+        self
+    }
+    /// Set L2-cache data ram setup latency enable
+    #[inline]
+    pub fn with_dsetup(mut self, dsetup: bool) -> Self {
+        self.bits.set_bit(19, dsetup);
+        self
+    }
+    /// Set L2-cache tag ram visit latency configuration
+    #[inline]
+    pub fn with_tltncy(mut self, tltncy: TLTNCY) -> Self {
+        self.bits.set_bits(22..=24, tltncy as usize);
+        self
+    }
+    /// Set L2-cache tag ram setup latency enable
+    #[inline]
+    pub fn with_tsetup(mut self, tsetup: bool) -> Self {
+        self.bits.set_bit(25, tsetup);
+        self
+    }
+    /// Set L2-cache instruction prefetch enable
+    #[inline]
+    pub fn with_iprf(mut self, iprf: IPRF) -> Self {
+        self.bits.set_bits(29..=30, iprf as usize);
+        self
+    }
+    /// Set L2-cache TLB prefetch enable
+    #[inline]
+    pub fn with_tprf(mut self, tprf: bool) -> Self {
+        self.bits.set_bit(31, tprf);
+        self
+    }
+    /// PAE enable, bit 32
+    ///
+    /// On RV32 this bit is held in `mccr2h`, see [`read64`] and [`write64`].
+    #[cfg(target_pointer_width = "64")]
+    #[inline]
+    pub fn pae(&self) -> bool {
+        self.bits.get_bit(32)
+    }
+    /// Set PAE enable
+    #[cfg(target_pointer_width = "64")]
+    #[inline]
+    pub fn with_pae(mut self, pae: bool) -> Self {
+        self.bits.set_bit(32, pae);
+        self
+    }
 }
 
 read_csr_as!(Mccr2, 0x7C3);
+write_csr_as!(Mccr2, 0x7C3);
+
+/// Reads the whole 64-bit register, including `mccr2h` (0x7CB) on RV32
+#[inline]
+pub fn read64() -> u64 {
+    csr::read64::<0x7C3, 0x7CB>()
+}
+
+/// Writes the whole 64-bit register, including `mccr2h` (0x7CB) on RV32
+///
+/// # Safety
+///
+/// Same as [`write`](fn@write); on RV32 both halves are written.
+#[inline]
+pub unsafe fn write64(bits: u64) {
+    csr::write64::<0x7C3, 0x7CB>(bits)
+}
 
 set_clear_csr! {
     /// Refill enable
@@ -123,6 +223,10 @@ set_clear_csr! {
     /// Error correction enable
     , 0x7C3, set_eccen, clear_eccen, 1 << 1
 }
+set_clear_csr! {
+    /// L2-cache enable
+    , 0x7C3, set_l2en, clear_l2en, 1 << 3
+}
 set_clear_csr! {
     /// L2-cache data ram setup latency enable
     , 0x7C3, set_dsetup, clear_dsetup, 1 << 19
@@ -135,30 +239,38 @@ set_clear_csr! {
     /// L2-cache TLB prefetch enable
     , 0x7C3, set_tprf, clear_tprf, 1 << 31
 }
+set_clear_csr! {
+    /// PAE enable
+    #[cfg(target_pointer_width = "64")]
+    , 0x7C3, set_pae, clear_pae, 1 << 32
+}
 
 /// L2-cache data ram visit latency configuration
+///
+/// # Safety
+///
+/// The L2-cache must be disabled, and the latency must match the SoC implementation.
 #[inline]
 pub unsafe fn set_dltncy(dltncy: DLTNCY) {
-    let mut value: usize;
-    asm!("csrr {}, 0x7C3", out(reg) value);
-    value.set_bits(16..=18, dltncy as usize);
-    asm!("csrw 0x7C3, {}", in(reg) value);
+    csr::write_field::<0x7C3>(0b111 << 16, (dltncy as usize) << 16);
 }
 
 /// L2-cache tag ram visit latency configuration
+///
+/// # Safety
+///
+/// The L2-cache must be disabled, and the latency must match the SoC implementation.
 #[inline]
 pub unsafe fn set_tltncy(tltncy: TLTNCY) {
-    let mut value: usize;
-    asm!("csrr {}, 0x7C3", out(reg) value);
-    value.set_bits(22..=24, tltncy as usize);
-    asm!("csrw 0x7C3, {}", in(reg) value);
+    csr::write_field::<0x7C3>(0b111 << 22, (tltncy as usize) << 22);
 }
 
 /// L2-cache instruction prefetch enable
+///
+/// # Safety
+///
+/// Must run in M mode.
 #[inline]
 pub unsafe fn set_iprf(iprf: IPRF) {
-    let mut value: usize;
-    asm!("csrr {}, 0x7C3", out(reg) value);
-    value.set_bits(29..=30, iprf as usize);
-    asm!("csrw 0x7C3, {}", in(reg) value);
+    csr::write_field::<0x7C3>(0b11 << 29, (iprf as usize) << 29);
 }
@@ -1,6 +1,6 @@
 //! mcdata{0,1}, machine cache data registers
 use bit_field::BitField;
-use core::arch::asm;
+use crate::csr;
 
 /// I-cache tag RAM visit result
 #[derive(Clone, Copy, Debug)]
@@ -68,25 +68,13 @@ pub fn get_dcache_data() -> DCacheData {
     }
 }
 
+/// Reads mcdata0 and mcdata1, including `mcdata0h` (0x7DB) and `mcdata1h` (0x7DC) on RV32
 #[inline]
-fn read_mcdata() -> (usize, usize) {
-    let (mcdata0, mcdata1);
-    unsafe {
-        asm!(
-            "csrr {0}, 0x7D4",
-            "csrr {1}, 0x7D5",
-            out(reg) mcdata0,
-            out(reg) mcdata1,
-        );
-    }
-    (mcdata0, mcdata1)
+fn read_mcdata() -> (u64, u64) {
+    (read_mcdata0(), csr::read64::<0x7D5, 0x7DC>())
 }
 
 #[inline]
-fn read_mcdata0() -> usize {
-    let mcdata0;
-    unsafe {
-        asm!("csrr {}, 0x7D4", out(reg) mcdata0);
-    }
-    mcdata0
+fn read_mcdata0() -> u64 {
+    csr::read64::<0x7D4, 0x7DB>()
 }
@@ -1,5 +1,7 @@
 //! mcer, machine L1-cache error control register
 use bit_field::BitField;
+use crate::csr;
+use super::Reserved;
 
 /// mcer register
 #[derive(Clone, Copy, Debug)]
@@ -25,6 +27,16 @@ pub enum RamId {
 }
 
 impl Mcer {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
     /// Error index
     #[inline]
     pub fn err_index(&self) -> u16 {
@@ -35,17 +47,27 @@ impl Mcer {
     pub fn err_way(&self) -> u8 {
         self.bits.get_bits(17..=18) as u8
     }
+    /// Error record comes from an ECC check
+    #[inline]
+    pub fn ecc_err(&self) -> bool {
+        self.bits.get_bit(19)
+    }
+    /// Error record comes from a bus error response
+    #[inline]
+    pub fn bus_err(&self) -> bool {
+        self.bits.get_bit(20)
+    }
     /// RAM that the error correction fatal error taken place
     #[inline]
-    pub fn ramid(&self) -> RamId {
+    pub fn ramid(&self) -> Result<RamId, Reserved> {
         match self.bits.get_bits(21..=23) {
-            0 => RamId::ICacheTag,
-            1 => RamId::ICacheData,
-            2 => RamId::DCacheTag,
-            3 => RamId::DCacheData,
-            4 => RamId::JTlbTag,
-            5 => RamId::JTlbData,
-            _ => unreachable!(),
+            0 => Ok(RamId::ICacheTag),
+            1 => Ok(RamId::ICacheData),
+            2 => Ok(RamId::DCacheTag),
+            3 => Ok(RamId::DCacheData),
+            4 => Ok(RamId::JTlbTag),
+            5 => Ok(RamId::JTlbData),
+            raw => Err(Reserved(raw)),
         }
     }
     /// Count of errors that are already fixed
@@ -63,12 +85,80 @@ impl Mcer {
     pub fn err_vld(&self) -> bool {
         self.bits.get_bit(31)
     }
-
-    // TODO BUS_ERR, ECC_ERR
+    /// Set error index
+    #[inline]
+    pub fn with_err_index(mut self, err_index: u16) -> Self {
+        self.bits.set_bits(0..=15, err_index as usize);
+        self
+    }
+    /// Set error way
+    #[inline]
+    pub fn with_err_way(mut self, err_way: u8) -> Self {
+        self.bits.set_bits(17..=18, err_way as usize);
+        self
+    }
+    /// Set whether error record comes from an ECC check
+    #[inline]
+    pub fn with_ecc_err(mut self, ecc_err: bool) -> Self {
+        self.bits.set_bit(19, ecc_err);
+        self
+    }
+    /// Set whether error record comes from a bus error response
+    #[inline]
+    pub fn with_bus_err(mut self, bus_err: bool) -> Self {
+        self.bits.set_bit(20, bus_err);
+        self
+    }
+    /// Set RAM that the error correction fatal error taken place
+    #[inline]
+    pub fn with_ramid(mut self, ramid: RamId) -> Self {
+        self.bits.set_bits(21..=23, ramid as usize);
+        self
+    }
+    /// Set count of errors that are already fixed
+    #[inline]
+    pub fn with_fix_cnt(mut self, fix_cnt: u8) -> Self {
+        self.bits.set_bits(24..=29, fix_cnt as usize);
+        self
+    }
+    /// Set whether L1-cache error correction error is a fatal error
+    #[inline]
+    pub fn with_err_fatal(mut self, err_fatal: bool) -> Self {
+        self.bits.set_bit(30, err_fatal);
+        self
+    }
+    /// Set error correction information valid
+    #[inline]
+    pub fn with_err_vld(mut self, err_vld: bool) -> Self {
+        self.bits.set_bit(31, err_vld);
+        self
+    }
 }
 
 read_csr_as!(Mcer, 0x7C8);
+write_csr_as!(Mcer, 0x7C8);
+
+/// Reads the register and clears its valid, fatal, ECC and bus error bits in one `csrrc`
+///
+/// Returns the record held before, so that an error recorded by hardware between reading and
+/// acknowledging the record is never lost.
+///
+/// # Safety
+///
+/// Must run in M mode. The returned record is no longer visible in `mcer`.
+#[inline]
+pub unsafe fn take() -> Mcer {
+    Mcer::from_bits(csr::read_clear::<0x7C8>((1 << 31) | (1 << 30) | (1 << 20) | (1 << 19)))
+}
 
+clear_csr! {
+    /// Clear ECC error bit
+    , 0x7C8, clear_ecc_err, 1 << 19
+}
+clear_csr! {
+    /// Clear bus error bit
+    , 0x7C8, clear_bus_err, 1 << 20
+}
 clear_csr! {
     /// Clear error correction fatal error bit
     , 0x7C8, clear_err_fatal, 1 << 30
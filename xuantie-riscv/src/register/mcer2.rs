@@ -1,5 +1,12 @@
 //! mcer2, machine L2-cache error control register
+//!
+//! # Platform support
+//!
+//! This register is supported on Xuantie C920 and C910 cores.
+pub use super::meicr2::L2RAMID;
 use bit_field::BitField;
+use crate::csr;
+use super::Reserved;
 
 /// mcer2 register
 #[derive(Clone, Copy, Debug)]
@@ -8,6 +15,16 @@ pub struct Mcer2 {
 }
 
 impl Mcer2 {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
     /// Error index
     #[inline]
     pub fn err_index(&self) -> u16 {
@@ -18,6 +35,16 @@ impl Mcer2 {
     pub fn err_way(&self) -> u8 {
         self.bits.get_bits(17..=20) as u8
     }
+    /// L2 RAM that the error correction error taken place
+    #[inline]
+    pub fn ramid(&self) -> Result<L2RAMID, Reserved> {
+        match self.bits.get_bits(21..=22) {
+            0 => Ok(L2RAMID::L2CacheTag),
+            1 => Ok(L2RAMID::L2CacheData),
+            2 => Ok(L2RAMID::L2CacheDirty),
+            raw => Err(Reserved(raw)),
+        }
+    }
     /// L2-cache 2 bit or parity error also happened elsewhere other than current position
     #[inline]
     pub fn oth_err(&self) -> bool {
@@ -28,9 +55,69 @@ impl Mcer2 {
     pub fn ecc_err(&self) -> bool {
         self.bits.get_bit(31)
     }
+    /// Set error index
+    #[inline]
+    pub fn with_err_index(mut self, err_index: u16) -> Self {
+        self.bits.set_bits(0..=15, err_index as usize);
+        self
+    }
+    /// Set error way
+    #[inline]
+    pub fn with_err_way(mut self, err_way: u8) -> Self {
+        self.bits.set_bits(17..=20, err_way as usize);
+        self
+    }
+    /// Set L2 RAM that the error correction error taken place
+    #[inline]
+    pub fn with_ramid(mut self, ramid: L2RAMID) -> Self {
+        self.bits.set_bits(21..=22, ramid as usize);
+        self
+    }
+    /// Set L2-cache 2 bit or parity error also happened elsewhere other than current position
+    #[inline]
+    pub fn with_oth_err(mut self, oth_err: bool) -> Self {
+        self.bits.set_bit(30, oth_err);
+        self
+    }
+    /// Set L2-cache 2 bit error correction error or parity check error
+    #[inline]
+    pub fn with_ecc_err(mut self, ecc_err: bool) -> Self {
+        self.bits.set_bit(31, ecc_err);
+        self
+    }
 }
 
 read_csr_as!(Mcer2, 0x7C4);
+write_csr_as!(Mcer2, 0x7C4);
+
+/// Reads the register and clears its error bits in one `csrrc`
+///
+/// Returns the record held before, so that an error recorded by hardware between reading and
+/// acknowledging the record is never lost. On RV32 `mcer2h` is not accessed.
+///
+/// # Safety
+///
+/// Must run in M mode. The returned record is no longer visible in `mcer2`.
+#[inline]
+pub unsafe fn take() -> Mcer2 {
+    Mcer2::from_bits(csr::read_clear::<0x7C4>((1 << 31) | (1 << 30)))
+}
+
+/// Reads the whole 64-bit register, including `mcer2h` (0x7FF) on RV32
+#[inline]
+pub fn read64() -> u64 {
+    csr::read64::<0x7C4, 0x7FF>()
+}
+
+/// Writes the whole 64-bit register, including `mcer2h` (0x7FF) on RV32
+///
+/// # Safety
+///
+/// Same as [`write`](fn@write); on RV32 both halves are written.
+#[inline]
+pub unsafe fn write64(bits: u64) {
+    csr::write64::<0x7C4, 0x7FF>(bits)
+}
 
 clear_csr! {
     /// Clear error correction information valid bit
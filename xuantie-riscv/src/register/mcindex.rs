@@ -1,6 +1,12 @@
 //! mcindex, machine cache visit index register
 use bit_field::BitField;
-use core::arch::asm;
+use crate::csr;
+
+/// mcindex register
+#[derive(Clone, Copy, Debug)]
+pub struct Mcindex {
+    bits: usize,
+}
 
 /// RAM information
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -15,43 +21,129 @@ pub enum RID {
     DCacheData = 3,
 }
 
+impl Mcindex {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
+    /// Cache index
+    #[inline]
+    pub fn index(&self) -> u32 {
+        self.bits.get_bits(0..=16) as u32
+    }
+    /// Way in level 1
+    #[inline]
+    pub fn way_l1(&self) -> u8 {
+        self.bits.get_bits(17..=20) as u8
+    }
+    /// Way in level 2
+    #[inline]
+    pub fn way_l2(&self) -> u8 {
+        self.bits.get_bits(21..=24) as u8
+    }
+    /// RAM to visit
+    #[inline]
+    pub fn rid(&self) -> RID {
+        match self.bits.get_bits(28..=29) {
+            0 => RID::ICacheTag,
+            1 => RID::ICacheData,
+            2 => RID::DCacheTag,
+            3 => RID::DCacheData,
+            _ => unreachable!(),
+        }
+    }
+    /// Set cache index
+    #[inline]
+    pub fn with_index(mut self, index: u32) -> Self {
+        self.bits.set_bits(0..=16, index as usize);
+        self
+    }
+    /// Set way in level 1
+    #[inline]
+    pub fn with_way_l1(mut self, way: u8) -> Self {
+        self.bits.set_bits(17..=20, way as usize);
+        self
+    }
+    /// Set way in level 2
+    #[inline]
+    pub fn with_way_l2(mut self, way: u8) -> Self {
+        self.bits.set_bits(21..=24, way as usize);
+        self
+    }
+    /// Set RAM to visit
+    #[inline]
+    pub fn with_rid(mut self, rid: RID) -> Self {
+        self.bits.set_bits(28..=29, rid as usize);
+        self
+    }
+}
+
+read_csr_as!(Mcindex, 0x7D3);
+write_csr_as!(Mcindex, 0x7D3);
+
+/// Reads the whole 64-bit register, including `mcindexh` (0x7DA) on RV32
+#[inline]
+pub fn read64() -> u64 {
+    csr::read64::<0x7D3, 0x7DA>()
+}
+
+/// Writes the whole 64-bit register, including `mcindexh` (0x7DA) on RV32
+///
+/// # Safety
+///
+/// Same as [`write`](fn@write); on RV32 both halves are written.
+#[inline]
+pub unsafe fn write64(bits: u64) {
+    csr::write64::<0x7D3, 0x7DA>(bits)
+}
+
 /// Set RAM to visit
+///
+/// # Safety
+///
+/// Must run in M mode, and no other code may use `mcindex` meanwhile.
 #[inline]
 pub unsafe fn set_rid(rid: RID) {
-    let mut value: usize;
-    asm!("csrr {}, 0x7D3", out(reg) value);
-    value.set_bits(28..=29, rid as usize);
-    asm!("csrw 0x7D3, {}", in(reg) value);
+    csr::write_field::<0x7D3>(0b11 << 28, (rid as usize) << 28);
 }
 
 /// Set way in level 1
+///
+/// # Safety
+///
+/// Must run in M mode, and no other code may use `mcindex` meanwhile.
 #[inline]
 pub unsafe fn set_way_l1(way: u8) {
     assert!(way <= 0b1111);
-    let mut value: usize;
-    asm!("csrr {}, 0x7D3", out(reg) value);
-    value.set_bits(17..=20, way as usize);
-    asm!("csrw 0x7D3, {}", in(reg) value);
+    csr::write_field::<0x7D3>(0b1111 << 17, (way as usize) << 17);
 }
 
 /// Set way in level 2
+///
+/// # Safety
+///
+/// Must run in M mode, and no other code may use `mcindex` meanwhile.
 #[inline]
 pub unsafe fn set_way_l2(way: u8) {
     assert!(way <= 0b1111);
-    let mut value: usize;
-    asm!("csrr {}, 0x7D3", out(reg) value);
-    value.set_bits(21..=24, way as usize);
-    asm!("csrw 0x7D3, {}", in(reg) value);
+    csr::write_field::<0x7D3>(0b1111 << 21, (way as usize) << 21);
 }
 
 /// Set cache index
 ///
 /// Lower 4 for 6 bits may be ignored according to RAM type and actual SoC implementation.
+///
+/// # Safety
+///
+/// Must run in M mode, and no other code may use `mcindex` meanwhile.
 #[inline]
 pub unsafe fn set_index(index: u32) {
     assert!(index <= 0x1FFFF);
-    let mut value: usize;
-    asm!("csrr {}, 0x7D3", out(reg) value);
-    value.set_bits(0..=16, index as usize);
-    asm!("csrw 0x7D3, {}", in(reg) value);
+    csr::write_field::<0x7D3>(0x1FFFF, index as usize);
 }
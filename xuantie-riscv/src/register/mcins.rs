@@ -1,4 +1,38 @@
 //! mcins, machine cache instruction register
+use bit_field::BitField;
+
+/// mcins register
+#[derive(Clone, Copy, Debug)]
+pub struct Mcins {
+    bits: usize,
+}
+
+impl Mcins {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
+    /// Cache read request is pending; cleared by hardware when the result is in `mcdata`
+    #[inline]
+    pub fn r(&self) -> bool {
+        self.bits.get_bit(0)
+    }
+    /// Set cache read request
+    #[inline]
+    pub fn with_r(mut self, r: bool) -> Self {
+        self.bits.set_bit(0, r);
+        self
+    }
+}
+
+read_csr_as!(Mcins, 0x7D2);
+write_csr_as!(Mcins, 0x7D2);
 
 set_clear_csr! {
     /// Cache read request
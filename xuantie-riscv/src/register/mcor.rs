@@ -1,9 +1,31 @@
 //! mcor, machine cache operation register
+//!
+//! Writing an operation bit starts the corresponding cache, predictor or TLB operation.
+//! Hardware clears the bit when the operation completes; use [`wait`] to wait for it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xuantie_riscv::register::mcor::{self, Invalidate};
+//! // invalidate indirect branch predictor and return address stack on context switch
+//! unsafe {
+//!     mcor::invalidate(Invalidate::IBP | Invalidate::RAS);
+//!     mcor::wait();
+//! }
+//! ```
 use bit_field::BitField;
-use core::arch::asm;
+use crate::csr;
+
+/// mcor register
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct Mcor {
+    bits: usize,
+}
 
 bitflags::bitflags! {
     /// Select cache to operate
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     pub struct Cache: usize {
         /// Select instruction cache
         const INSTRUCTION = 1 << 0;
@@ -16,6 +38,7 @@ bitflags::bitflags! {
 
 bitflags::bitflags! {
     /// Select operation for caches
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     pub struct Operation: usize {
         /// Invalidate cache
         const INVALIDATE = 1 << 4;
@@ -24,26 +47,259 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// Select branch predictor and TLB structures to invalidate
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Invalidate: usize {
+        /// Branch history table
+        const BHT = 1 << 16;
+        /// Branch target buffer
+        const BTB = 1 << 17;
+        /// Indirect branch predictor
+        ///
+        /// # Platform support
+        ///
+        /// This bit is supported on Xuantie C920 and C910 cores.
+        #[cfg(any(xuantie_c920, xuantie_c910))]
+        const IBP = 1 << 18;
+        /// Translation lookaside buffer
+        ///
+        /// # Platform support
+        ///
+        /// This bit is supported on Xuantie C920 and C910 cores.
+        #[cfg(any(xuantie_c920, xuantie_c910))]
+        const TLB = 1 << 19;
+        /// Level 0 branch target buffer
+        ///
+        /// # Platform support
+        ///
+        /// This bit is supported on Xuantie C920 and C910 cores.
+        #[cfg(any(xuantie_c920, xuantie_c910))]
+        const L0BTB = 1 << 20;
+        /// Return address stack
+        ///
+        /// # Platform support
+        ///
+        /// This bit is supported on Xuantie C920 and C910 cores.
+        #[cfg(any(xuantie_c920, xuantie_c910))]
+        const RAS = 1 << 21;
+    }
+}
+
+/// Mask of all bits that hardware clears when their operation completes.
+const BUSY: usize = Operation::all().bits() | Invalidate::all().bits();
+
+impl Mcor {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
+    /// Selected cache
+    #[inline]
+    pub fn cache(&self) -> Cache {
+        Cache::from_bits_truncate(self.bits.get_bits(0..=1))
+    }
+    /// Pending cache operation
+    #[inline]
+    pub fn operation(&self) -> Operation {
+        Operation::from_bits_truncate(self.bits)
+    }
+    /// Pending branch predictor and TLB invalidation
+    #[inline]
+    pub fn invalidate(&self) -> Invalidate {
+        Invalidate::from_bits_truncate(self.bits)
+    }
+    /// Is cache invalidation in progress
+    #[inline]
+    pub fn inv(&self) -> bool {
+        self.bits.get_bit(4)
+    }
+    /// Is cache clear in progress
+    #[inline]
+    pub fn clr(&self) -> bool {
+        self.bits.get_bit(5)
+    }
+    /// Is branch history table invalidation in progress
+    #[inline]
+    pub fn bht_inv(&self) -> bool {
+        self.bits.get_bit(16)
+    }
+    /// Is branch target buffer invalidation in progress
+    #[inline]
+    pub fn btb_inv(&self) -> bool {
+        self.bits.get_bit(17)
+    }
+    /// Is indirect branch predictor invalidation in progress
+    #[inline]
+    pub fn ibp_inv(&self) -> bool {
+        self.bits.get_bit(18)
+    }
+    /// Is TLB invalidation in progress
+    #[inline]
+    pub fn tlb_inv(&self) -> bool {
+        self.bits.get_bit(19)
+    }
+    /// Is level 0 branch target buffer invalidation in progress
+    #[inline]
+    pub fn l0btb_inv(&self) -> bool {
+        self.bits.get_bit(20)
+    }
+    /// Is return address stack invalidation in progress
+    #[inline]
+    pub fn ras_inv(&self) -> bool {
+        self.bits.get_bit(21)
+    }
+    /// Is any operation started through this register still in progress
+    #[inline]
+    pub fn busy(&self) -> bool {
+        self.bits & BUSY != 0
+    }
+    /// Set selected cache
+    #[inline]
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.bits.set_bits(0..=1, cache.bits());
+        self
+    }
+    /// Set cache operation
+    #[inline]
+    pub fn with_operation(mut self, op: Operation) -> Self {
+        self.bits = (self.bits & !Operation::all().bits()) | op.bits();
+        self
+    }
+    /// Set branch predictor and TLB invalidation
+    #[inline]
+    pub fn with_invalidate(mut self, inv: Invalidate) -> Self {
+        self.bits = (self.bits & !Invalidate::all().bits()) | inv.bits();
+        self
+    }
+}
+
+read_csr_as!(Mcor, 0x7C2);
+write_csr_as!(Mcor, 0x7C2);
+
 /// Perform cache operation
+///
+/// Stale selection and operation bits are cleared first, then the new cache selection and
+/// operation are set together in one `csrs`. Both instructions are atomic, so other bits of
+/// `mcor` changed by a trap handler in between are kept, and no cache operation is started
+/// on a cache that was not selected.
+///
+/// # Safety
+///
+/// Invalidating discards dirty lines without writing them back; the caller must ensure no
+/// memory still in use has unwritten data in the selected caches. Must run in M mode.
 #[inline]
 pub unsafe fn cache(cache: Cache, op: Operation) {
     let bits = cache.bits() | op.bits();
-    let mut value: usize;
-    asm!("csrr {}, 0x7C2", out(reg) value);
-    value.set_bits(0..=5, bits as usize);
-    asm!("csrw 0x7C2, {}", in(reg) value);
+    csr::clear::<0x7C2>(0b11_1111 & !bits);
+    csr::set::<0x7C2>(bits);
+}
+
+/// Invalidate branch predictor and TLB structures
+///
+/// All selected structures are invalidated with one `csrs`.
+///
+/// # Safety
+///
+/// Must run in M mode. Invalidating the TLB requires page tables to be consistent with
+/// the mappings in use.
+#[inline]
+pub unsafe fn invalidate(inv: Invalidate) {
+    csr::set::<0x7C2>(inv.bits());
 }
 
 /// Invalidate branch history table
+///
+/// # Safety
+///
+/// Must run in M mode.
 #[inline]
 pub unsafe fn bht_inv() {
-    asm!("csrs 0x7C2, {}", in(reg) 1 << 16);
+    invalidate(Invalidate::BHT);
 }
 
 /// Invalidate branch target buffer table
+///
+/// # Safety
+///
+/// Must run in M mode.
 #[inline]
 pub unsafe fn btb_inv() {
-    asm!("csrs 0x7C2, {}", in(reg) 1 << 17);
+    invalidate(Invalidate::BTB);
+}
+
+/// Invalidate indirect branch predictor
+///
+/// # Safety
+///
+/// Must run in M mode.
+///
+/// # Platform support
+///
+/// This bit is supported on Xuantie C920 and C910 cores.
+#[cfg(any(xuantie_c920, xuantie_c910))]
+#[inline]
+pub unsafe fn ibp_inv() {
+    invalidate(Invalidate::IBP);
+}
+
+/// Invalidate translation lookaside buffer
+///
+/// # Safety
+///
+/// Must run in M mode, with page tables consistent with the mappings in use.
+///
+/// # Platform support
+///
+/// This bit is supported on Xuantie C920 and C910 cores.
+#[cfg(any(xuantie_c920, xuantie_c910))]
+#[inline]
+pub unsafe fn tlb_inv() {
+    invalidate(Invalidate::TLB);
+}
+
+/// Invalidate level 0 branch target buffer
+///
+/// # Safety
+///
+/// Must run in M mode.
+///
+/// # Platform support
+///
+/// This bit is supported on Xuantie C920 and C910 cores.
+#[cfg(any(xuantie_c920, xuantie_c910))]
+#[inline]
+pub unsafe fn l0btb_inv() {
+    invalidate(Invalidate::L0BTB);
 }
 
-// TODO IBP_INV, TLB_INV, L0BTB_INV, RAS_INV bits.
+/// Invalidate return address stack
+///
+/// # Safety
+///
+/// Must run in M mode.
+///
+/// # Platform support
+///
+/// This bit is supported on Xuantie C920 and C910 cores.
+#[cfg(any(xuantie_c920, xuantie_c910))]
+#[inline]
+pub unsafe fn ras_inv() {
+    invalidate(Invalidate::RAS);
+}
+
+/// Wait until every operation started through `mcor` has completed
+///
+/// Hardware clears each operation bit once the operation is done.
+#[inline]
+pub fn wait() {
+    while read().busy() {
+        core::hint::spin_loop();
+    }
+}
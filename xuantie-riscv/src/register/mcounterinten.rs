@@ -0,0 +1,14 @@
+//! mcounterinten, machine counter overflow interrupt enable register
+//!
+//! When a bit is set, overflow of the corresponding counter raises the XuanTie PMU
+//! overflow interrupt.
+//!
+//! # Platform support
+//!
+//! This register is supported on Xuantie C920, C910 and C906 cores on RV64. On RV32 its
+//! address is used by `mhint2h`.
+
+counter_csr! {
+    /// mcounterinten register
+    Mcounterinten, 0x7CA
+}
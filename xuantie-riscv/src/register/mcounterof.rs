@@ -0,0 +1,28 @@
+//! mcounterof, machine counter overflow register
+//!
+//! Hardware sets a bit when the corresponding counter overflows. Overflow handlers
+//! acknowledge an overflow by clearing its bit, for example with [`clear_hpm`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xuantie_riscv::register::mcounterof;
+//! #[unsafe(no_mangle)]
+//! extern "C" fn THeadPmuOverflow() {
+//!     let overflow = mcounterof::read();
+//!     for index in (3..=31).filter(|&index| overflow.hpm(index)) {
+//!         // handle overflow of mhpmcounter{index} here, then acknowledge it
+//!         unsafe { mcounterof::clear_hpm(index) };
+//!     }
+//! }
+//! ```
+//!
+//! # Platform support
+//!
+//! This register is supported on Xuantie C920, C910 and C906 cores on RV64. On RV32 its
+//! address is used by `mccr2h`.
+
+counter_csr! {
+    /// mcounterof register
+    Mcounterof, 0x7CB
+}
@@ -0,0 +1,14 @@
+//! mcounterwen, machine counter write enable register
+//!
+//! When a bit is set, supervisor mode may write the corresponding `shpmcounter`
+//! (or `scycle`, `sinstret`) register. Otherwise such writes raise an illegal instruction
+//! exception.
+//!
+//! # Platform support
+//!
+//! This register is supported on Xuantie C920, C910 and C906 cores.
+
+counter_csr! {
+    /// mcounterwen register
+    Mcounterwen, 0x7C9
+}
@@ -1,38 +1,176 @@
 //! mcpuid, processor identification register
 //!
+//! Each read of `mcpuid` returns one of seven index words, selected by bits 28..=31, and
+//! successive reads cycle through all of them. [`read`] collects every index word.
+//!
+//! | Index | Contents |
+//! |:------|:---------|
+//! | 0     | Architecture, family, class and model |
+//! | 1     | Revision, sub-revision and patch version |
+//! | 2     | Bus information |
+//! | 3, 4  | Cache information |
+//! | 5, 6  | Feature information |
+//!
+//! The vendor is read from the standard `mvendorid` register.
+//!
 //! # Platform support
 //!
 //! This register is supported on Xuantie C910, C906, E907, E906 and E902 cores.
-//!
-//! todo: verify mcpuid read and look it up on C906 manual
 use bit_field::BitField;
-use core::arch::asm;
+use crate::csr;
+
+/// T-Head JEDEC vendor ID, as reported by `mvendorid`.
+pub const THEAD_VENDOR_ID: usize = 0x5B7;
 
 /// Processor information
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Cpuid {
-    #[allow(unused)] // todo: remove when all fields implemented
+    vendor: usize,
     data: [u32; 7],
+    valid: u8,
+}
+
+/// Base architecture
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Arch {
+    /// 32-bit RISC-V
+    Rv32,
+    /// 64-bit RISC-V
+    Rv64,
 }
 
-// todo: all the fields
 impl Cpuid {
-    // pub fn arch(self) -> usize {
+    /// Build processor information from the `mvendorid` value and raw `mcpuid` index words.
+    ///
+    /// Words are placed according to their index bits; words with an invalid index are ignored.
+    #[inline]
+    pub fn from_words(vendor: usize, words: &[u32]) -> Self {
+        let mut cpuid = Cpuid {
+            vendor,
+            data: [0; 7],
+            valid: 0,
+        };
+        for &word in words {
+            if let idx @ 0..=6 = word.get_bits(28..=31) {
+                cpuid.data[idx as usize] = word;
+                cpuid.valid |= 1 << idx;
+            }
+        }
+        cpuid
+    }
+    /// Raw index word `index`, or `None` if it was not returned by the processor
+    #[inline]
+    pub fn word(&self, index: usize) -> Option<u32> {
+        if index < 7 && self.valid & (1 << index) != 0 {
+            Some(self.data[index])
+        } else {
+            None
+        }
+    }
+    /// Payload of index word `index`, bits 0..=27
+    #[inline]
+    fn payload(&self, index: usize) -> Option<u32> {
+        self.word(index).map(|word| word.get_bits(0..=27))
+    }
+    /// Vendor ID from `mvendorid`
+    #[inline]
+    pub fn vendor(&self) -> usize {
+        self.vendor
+    }
+    /// Is this a T-Head processor
+    #[inline]
+    pub fn is_thead(&self) -> bool {
+        self.vendor == THEAD_VENDOR_ID
+    }
+    /// Base architecture
+    #[inline]
+    pub fn arch(&self) -> Option<Arch> {
+        match self.word(0)?.get_bits(26..=27) {
+            0b00 => Some(Arch::Rv32),
+            0b10 => Some(Arch::Rv64),
+            _ => None,
+        }
+    }
+    /// Processor family, such as the E or C series
+    #[inline]
+    pub fn family(&self) -> Option<u8> {
+        Some(self.word(0)?.get_bits(22..=25) as u8)
+    }
+    /// Processor class within its family
+    #[inline]
+    pub fn class(&self) -> Option<u8> {
+        Some(self.word(0)?.get_bits(18..=21) as u8)
+    }
+    /// Processor model within its class
+    #[inline]
+    pub fn model(&self) -> Option<u16> {
+        Some(self.word(0)?.get_bits(8..=17) as u16)
+    }
+    /// Major revision
+    #[inline]
+    pub fn revision(&self) -> Option<u8> {
+        Some(self.word(1)?.get_bits(24..=27) as u8)
+    }
+    /// Sub-revision
+    #[inline]
+    pub fn sub_revision(&self) -> Option<u8> {
+        Some(self.word(1)?.get_bits(18..=23) as u8)
+    }
+    /// Patch version
+    #[inline]
+    pub fn patch(&self) -> Option<u8> {
+        Some(self.word(1)?.get_bits(12..=17) as u8)
+    }
+    /// Version as `(revision, sub_revision, patch)`
+    #[inline]
+    pub fn version(&self) -> Option<(u8, u8, u8)> {
+        Some((self.revision()?, self.sub_revision()?, self.patch()?))
+    }
+    /// Bus information word, bits 0..=27 of index 2
+    #[inline]
+    pub fn bus_info(&self) -> Option<u32> {
+        self.payload(2)
+    }
+    /// Cache information, bits 0..=27 of index 3 followed by bits 0..=27 of index 4
+    #[inline]
+    pub fn cache_info(&self) -> Option<u64> {
+        Some(self.payload(3)? as u64 | (self.payload(4)? as u64) << 28)
+    }
+    /// L1 I-cache size in bytes, from bits 0..=3 of the cache information
+    ///
+    /// The field holds the size as a power of two in Kibibytes; zero means no I-cache.
+    #[inline]
+    pub fn icache_size(&self) -> Option<usize> {
+        cache_size(self.cache_info()?.get_bits(0..=3) as u32)
+    }
+    /// L1 D-cache size in bytes, from bits 4..=7 of the cache information
+    ///
+    /// The field holds the size as a power of two in Kibibytes; zero means no D-cache.
+    #[inline]
+    pub fn dcache_size(&self) -> Option<usize> {
+        cache_size(self.cache_info()?.get_bits(4..=7) as u32)
+    }
+    /// Feature information, bits 0..=27 of index 5 followed by bits 0..=27 of index 6
+    #[inline]
+    pub fn feature_info(&self) -> Option<u64> {
+        Some(self.payload(5)? as u64 | (self.payload(6)? as u64) << 28)
+    }
+}
 
-    // }
+#[inline]
+fn cache_size(code: u32) -> Option<usize> {
+    match code {
+        0 => None,
+        code => Some(1024 << code),
+    }
 }
 
 /// Fetch the processor information
 #[inline]
 pub fn read() -> Cpuid {
-    let mut data = [0; 7];
-    for _ in 0..7 {
-        let val: u32;
-        unsafe { asm!("csrr {}, 0xFC0", out(reg) val) };
-        match val.get_bits(28..=31) {
-            idx @ 0..=6 => data[idx as usize] = val,
-            _ => {} // ignore invalid data indexes
-        }
+    let mut words = [0; 7];
+    for word in words.iter_mut() {
+        *word = csr::read::<0xFC0>() as u32;
     }
-    Cpuid { data }
+    Cpuid::from_words(csr::read::<0xF11>(), &words)
 }
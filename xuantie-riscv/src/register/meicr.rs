@@ -1,5 +1,5 @@
 //! meicr, L1-cache hardware fault inject register
-use core::arch::asm;
+use crate::csr;
 
 pub use super::mcer::RamId;
 
@@ -16,8 +16,13 @@ set_clear_csr! {
 ///
 /// If `fatal_inj` is `1`, inject a 2-bit error; if `fatal_inj` is `0`, inject a 1-bit error.
 /// Set `inj_en` to `1` to start L1-cache error control error injection.
+///
+/// # Safety
+///
+/// Injected errors corrupt cache contents; the caller must only inject into lines that
+/// are invalidated afterwards.
 #[inline]
 pub unsafe fn write(inj_en: bool, fatal_inj: bool, ramid: RamId) {
     let bits = inj_en as usize | ((fatal_inj as usize) << 1) | ((ramid as usize) << 29);
-    asm!("csrw 0x7D6, {}", in(reg) bits)
+    csr::write::<0x7D6>(bits);
 }
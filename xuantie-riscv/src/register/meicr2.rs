@@ -1,5 +1,5 @@
 //! meicr2, L2-cache hardware fault inject register
-use core::arch::asm;
+use crate::csr;
 
 /// L2 error controllable RAM index
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -25,8 +25,13 @@ set_clear_csr! {
 ///
 /// If `fatal_inj` is `1`, inject a 2-bit error; if `fatal_inj` is `0`, inject a 1-bit error.
 /// Set `inj_en` to `1` to start L2-cache error control error injection.
+///
+/// # Safety
+///
+/// Injected errors corrupt cache contents; the caller must only inject into lines that
+/// are invalidated afterwards.
 #[inline]
 pub unsafe fn write(inj_en: bool, fatal_inj: bool, ramid: L2RAMID) {
     let bits = inj_en as usize | ((fatal_inj as usize) << 1) | ((ramid as usize) << 29);
-    asm!("csrw 0x7D7, {}", in(reg) bits)
+    csr::write::<0x7D7>(bits);
 }
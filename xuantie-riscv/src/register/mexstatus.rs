@@ -4,7 +4,8 @@
 //!
 //! This register is supported on Xuantie E907, E906 and E902 cores.
 use bit_field::BitField;
-use core::arch::asm;
+use crate::csr;
+use super::Reserved;
 
 /// mxstatus register
 #[derive(Clone, Copy, Debug)]
@@ -34,23 +35,33 @@ pub enum LPMD {
 }
 
 impl Mexstatus {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
     /// Software reset mode
     #[inline]
-    pub fn rstmd(&self) -> RSTMD {
+    pub fn rstmd(&self) -> Result<RSTMD, Reserved> {
         match self.bits.get_bits(0..=1) {
-            0b00 => RSTMD::Nop,
-            0b01 => RSTMD::ResetCore,
-            0b10 => RSTMD::ResetSystem,
-            _ => unreachable!(),
+            0b00 => Ok(RSTMD::Nop),
+            0b01 => Ok(RSTMD::ResetCore),
+            0b10 => Ok(RSTMD::ResetSystem),
+            raw => Err(Reserved(raw)),
         }
     }
     /// Low power mode
     #[inline]
-    pub fn lpmd(&self) -> LPMD {
+    pub fn lpmd(&self) -> Result<LPMD, Reserved> {
         match self.bits.get_bits(2..=3) {
-            0b00 => LPMD::DeepSleep,
-            0b01 => LPMD::LightSleep,
-            _ => unreachable!(),
+            0b00 => Ok(LPMD::DeepSleep),
+            0b01 => Ok(LPMD::LightSleep),
+            raw => Err(Reserved(raw)),
         }
     }
     /// Wait for event mode enble
@@ -88,9 +99,58 @@ impl Mexstatus {
     pub fn spswapen(&self) -> bool {
         self.bits.get_bit(17)
     }
+    /// Set software reset mode
+    #[inline]
+    pub fn with_rstmd(mut self, rstmd: RSTMD) -> Self {
+        self.bits.set_bits(0..=1, rstmd as usize);
+        self
+    }
+    /// Set low power mode
+    #[inline]
+    pub fn with_lpmd(mut self, lpmd: LPMD) -> Self {
+        self.bits.set_bits(2..=3, lpmd as usize);
+        self
+    }
+    /// Set wait for event mode enble
+    #[inline]
+    pub fn with_wfeen(mut self, wfeen: bool) -> Self {
+        self.bits.set_bit(4, wfeen);
+        self
+    }
+    /// Set exception state
+    #[inline]
+    pub fn with_expt(mut self, expt: bool) -> Self {
+        self.bits.set_bit(5, expt);
+        self
+    }
+    /// Set NMI state
+    #[inline]
+    pub fn with_nmi(mut self, nmi: bool) -> Self {
+        self.bits.set_bit(7, nmi);
+        self
+    }
+    /// Set bus error state
+    #[inline]
+    pub fn with_buserr(mut self, buserr: bool) -> Self {
+        self.bits.set_bit(8, buserr);
+        self
+    }
+    /// Set interrupt auto push stack enable
+    #[inline]
+    pub fn with_spushen(mut self, spushen: bool) -> Self {
+        self.bits.set_bit(16, spushen);
+        self
+    }
+    /// Set interrupt auto swap stack enable
+    #[inline]
+    pub fn with_spswapen(mut self, spswapen: bool) -> Self {
+        self.bits.set_bit(17, spswapen);
+        self
+    }
 }
 
 read_csr_as!(Mexstatus, 0x7E1);
+write_csr_as!(Mexstatus, 0x7E1);
 
 set_clear_csr! {
     /// Wait for event mode enble
@@ -106,16 +166,21 @@ set_clear_csr! {
 }
 
 /// Set software reset mode
+///
+/// # Safety
+///
+/// Requesting a reset stops all code running on this hart.
 #[inline]
 pub unsafe fn set_rstmd(rstmd: RSTMD) {
-    asm!("csrs 0x7E1, {}", in(reg) rstmd as usize)
+    csr::write_field::<0x7E1>(0b11, rstmd as usize);
 }
 
 /// Set low power mode
+///
+/// # Safety
+///
+/// Changes what the next `wfi` does; wake-up sources for the chosen mode must be set up.
 #[inline]
 pub unsafe fn set_lpmd(lpmd: LPMD) {
-    let mut value: usize;
-    asm!("csrr {}, 0x7E1", out(reg) value);
-    value.set_bits(2..=3, lpmd as usize);
-    asm!("csrw 0x7E1, {}", in(reg) value);
+    csr::write_field::<0x7E1>(0b11 << 2, (lpmd as usize) << 2);
 }
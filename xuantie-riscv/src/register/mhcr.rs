@@ -13,11 +13,24 @@ pub struct Mhcr {
 }
 
 impl Mhcr {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
+
     /// I-cache enable
     ///
     /// # Platform support
     ///
     /// This bit is supported on Xuantie C920, C910, C906, E907, E906 and E902 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906, xuantie_e902))]
     #[inline]
     pub fn ie(&self) -> bool {
         self.bits.get_bit(0)
@@ -28,6 +41,7 @@ impl Mhcr {
     /// # Platform support
     ///
     /// This bit is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     #[inline]
     pub fn de(&self) -> bool {
         self.bits.get_bit(1)
@@ -38,6 +52,7 @@ impl Mhcr {
     /// # Platform support
     ///
     /// This bit is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     #[inline]
     pub fn wa(&self) -> bool {
         self.bits.get_bit(2)
@@ -48,6 +63,7 @@ impl Mhcr {
     /// # Platform support
     ///
     /// This bit is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     #[inline]
     pub fn wb(&self) -> bool {
         self.bits.get_bit(3)
@@ -58,6 +74,7 @@ impl Mhcr {
     /// # Platform support
     ///
     /// This bit is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     #[inline]
     pub fn rs(&self) -> bool {
         self.bits.get_bit(4)
@@ -68,6 +85,7 @@ impl Mhcr {
     /// # Platform support
     ///
     /// This bit is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     #[inline]
     pub fn bpe(&self) -> bool {
         self.bits.get_bit(5)
@@ -78,6 +96,7 @@ impl Mhcr {
     /// # Platform support
     ///
     /// This bit is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     #[inline]
     pub fn btb(&self) -> bool {
         self.bits.get_bit(6)
@@ -87,41 +106,112 @@ impl Mhcr {
     ///
     /// # Platform support
     ///
-    /// This bit is supported on Xuantie C920 and TODO cores.
+    /// This bit is supported on Xuantie C920, C910 and C906 cores.
     #[inline]
     pub fn wbr(&self) -> bool {
         self.bits.get_bit(8)
     }
+
+    /// Set I-cache enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906, xuantie_e902))]
+    #[inline]
+    pub fn with_ie(mut self, ie: bool) -> Self {
+        self.bits.set_bit(0, ie);
+        self
+    }
+
+    /// Set D-cache enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_de(mut self, de: bool) -> Self {
+        self.bits.set_bit(1, de);
+        self
+    }
+
+    /// Set cache write allocate configuration enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_wa(mut self, wa: bool) -> Self {
+        self.bits.set_bit(2, wa);
+        self
+    }
+
+    /// Set write back enable; false for write through
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_wb(mut self, wb: bool) -> Self {
+        self.bits.set_bit(3, wb);
+        self
+    }
+
+    /// Set return stack enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_rs(mut self, rs: bool) -> Self {
+        self.bits.set_bit(4, rs);
+        self
+    }
+
+    /// Set branch predict enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_bpe(mut self, bpe: bool) -> Self {
+        self.bits.set_bit(5, bpe);
+        self
+    }
+
+    /// Set branch target buffer enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_btb(mut self, btb: bool) -> Self {
+        self.bits.set_bit(6, btb);
+        self
+    }
+
+    /// Set write bulk transfer enable
+    #[inline]
+    pub fn with_wbr(mut self, wbr: bool) -> Self {
+        self.bits.set_bit(8, wbr);
+        self
+    }
 }
 
 read_csr_as!(Mhcr, 0x7C1);
+write_csr_as!(Mhcr, 0x7C1);
 
 set_clear_csr! {
     /// I-cache enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906, xuantie_e902))]
     , 0x7C1, set_ie, clear_ie, 1 << 0
 }
 set_clear_csr! {
     /// D-cache enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     , 0x7C1, set_de, clear_de, 1 << 1
 }
 set_clear_csr! {
     /// Cache write allocate configuration enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     , 0x7C1, set_wa, clear_wa, 1 << 2
 }
 set_clear_csr! {
     /// Write back enable; clear this bit to be write through
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     , 0x7C1, set_wb, clear_wb, 1 << 3
 }
 set_clear_csr! {
     /// Return stack enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     , 0x7C1, set_rs, clear_rs, 1 << 4
 }
 set_clear_csr! {
     /// Branch predict enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     , 0x7C1, set_bpe, clear_bpe, 1 << 5
 }
 set_clear_csr! {
     /// Branch target buffer enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     , 0x7C1, set_btb, clear_btb, 1 << 6
 }
 set_clear_csr! {
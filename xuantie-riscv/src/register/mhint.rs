@@ -39,11 +39,33 @@
 //!
 //! This register is supported on Xuantie C920, C910, C906, E907 and E906 cores.
 use bit_field::BitField;
-use core::arch::asm;
+use crate::csr;
 
-// TODO struct Mhint
+/// mhint register
+///
+/// # Example
+///
+/// ```
+/// use xuantie_riscv::register::mhint::{self, PrefN, AMR};
+/// // enable I-cache and D-cache prefetch, prefetching 16 D-cache lines
+/// unsafe {
+///     mhint::modify(|r| {
+///         r.with_ipld(true)
+///             .with_dpld(true)
+///             .with_amr(AMR::After3Lines)
+///             .with_prefn(PrefN::SixteenLines)
+///     })
+/// };
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct Mhint {
+    bits: usize,
+}
 
-/// L1 D-cache write allocation strategy
+/// D-cache write allocation strategy
+///
+/// Used by both the L1 (`AMR`) and the L2 (`AMR2`) write allocation fields.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum AMR {
     /// Depends on WA page entry attibute
@@ -69,12 +91,464 @@ pub enum PrefN {
     SixteenLines = 3,
 }
 
+/// L2-cache prefetch distance
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum L2Dis {
+    /// Prefetch 8 lines ahead
+    EightLines = 0,
+    /// Prefetch 16 lines ahead
+    SixteenLines = 1,
+    /// Prefetch 32 lines ahead
+    ThirtyTwoLines = 2,
+    /// Prefetch 64 lines ahead
+    SixtyFourLines = 3,
+}
+
+impl Mhint {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// D-cache prefetch enable.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn dpld(&self) -> bool {
+        self.bits.get_bit(2)
+    }
+
+    /// L1 D-cache write allocation strategy.
+    ///
+    /// # Platform support
+    ///
+    /// This field is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn amr(&self) -> AMR {
+        match self.bits.get_bits(3..=4) {
+            0 => AMR::DependsOnWA,
+            1 => AMR::After3Lines,
+            2 => AMR::After64Lines,
+            3 => AMR::After128Lines,
+            _ => unreachable!(),
+        }
+    }
+
+    /// L2-cache write allocation strategy.
+    ///
+    /// # Platform support
+    ///
+    /// This field is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn amr2(&self) -> AMR {
+        match self.bits.get_bits(5..=6) {
+            0 => AMR::DependsOnWA,
+            1 => AMR::After3Lines,
+            2 => AMR::After64Lines,
+            3 => AMR::After128Lines,
+            _ => unreachable!(),
+        }
+    }
+
+    /// I-cache prefetch enable.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn ipld(&self) -> bool {
+        self.bits.get_bit(8)
+    }
+
+    /// I-cache way prediction enable.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920, C910 and C906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906))]
+    #[inline]
+    pub fn iwpe(&self) -> bool {
+        self.bits.get_bit(9)
+    }
+
+    /// Loop acceleration enable.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn lpe(&self) -> bool {
+        self.bits.get_bit(10)
+    }
+
+    /// Store stream read enable; consecutive full-line stores skip the line fill.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn sre(&self) -> bool {
+        self.bits.get_bit(11)
+    }
+
+    /// D-cache prefetch lines, named `D_DIS` in C9xx manuals.
+    ///
+    /// # Platform support
+    ///
+    /// This field is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn prefn(&self) -> PrefN {
+        match self.bits.get_bits(13..=14) {
+            0 => PrefN::TwoLines,
+            1 => PrefN::FourLines,
+            2 => PrefN::EightLines,
+            3 => PrefN::SixteenLines,
+            _ => unreachable!(),
+        }
+    }
+
+    /// L2-cache prefetch enable.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn l2pld(&self) -> bool {
+        self.bits.get_bit(15)
+    }
+
+    /// L2-cache prefetch distance.
+    ///
+    /// # Platform support
+    ///
+    /// This field is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn l2_dis(&self) -> L2Dis {
+        match self.bits.get_bits(16..=17) {
+            0 => L2Dis::EightLines,
+            1 => L2Dis::SixteenLines,
+            2 => L2Dis::ThirtyTwoLines,
+            3 => L2Dis::SixtyFourLines,
+            _ => unreachable!(),
+        }
+    }
+
+    /// L2-cache store prefetch enable.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn l2stpld(&self) -> bool {
+        self.bits.get_bit(18)
+    }
+
+    /// Accure exception enable.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie E907 and E906 cores.
+    #[cfg(any(xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn aee(&self) -> bool {
+        self.bits.get_bit(20)
+    }
+
+    /// TLB maintenance broadcast disable.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn tlb_broad_dis(&self) -> bool {
+        self.bits.get_bit(21)
+    }
+
+    /// Speculative memory access disable.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn no_spec(&self) -> bool {
+        self.bits.get_bit(22)
+    }
+
+    /// L1-cache error correction enable.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn ecc(&self) -> bool {
+        self.bits.get_bit(23)
+    }
+
+    /// Error correction write back disable; errors are detected but not corrected.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn corr_dis(&self) -> bool {
+        self.bits.get_bit(24)
+    }
+
+    /// TLB prefetch enable.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn tlbpld(&self) -> bool {
+        self.bits.get_bit(25)
+    }
+
+    /// Debug PC FIFO freeze.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn pcfifo_freeze(&self) -> bool {
+        self.bits.get_bit(26)
+    }
+
+    /// `wrs.nto` and `wrs.sto` wait disable; the instructions retire immediately.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn wrs_dis(&self) -> bool {
+        self.bits.get_bit(27)
+    }
+
+    /// Cache block coherence flush; `cbo` instructions also operate on the L2-cache.
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn cbcf(&self) -> bool {
+        self.bits.get_bit(28)
+    }
+
+    /// Set D-cache prefetch enable.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_dpld(mut self, dpld: bool) -> Self {
+        self.bits.set_bit(2, dpld);
+        self
+    }
+
+    /// Set L1 D-cache write allocation strategy.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_amr(mut self, amr: AMR) -> Self {
+        self.bits.set_bits(3..=4, amr as usize);
+        self
+    }
+
+    /// Set L2-cache write allocation strategy.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn with_amr2(mut self, amr2: AMR) -> Self {
+        self.bits.set_bits(5..=6, amr2 as usize);
+        self
+    }
+
+    /// Set I-cache prefetch enable.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_ipld(mut self, ipld: bool) -> Self {
+        self.bits.set_bit(8, ipld);
+        self
+    }
+
+    /// Set I-cache way prediction enable.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906))]
+    #[inline]
+    pub fn with_iwpe(mut self, iwpe: bool) -> Self {
+        self.bits.set_bit(9, iwpe);
+        self
+    }
+
+    /// Set loop acceleration enable.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn with_lpe(mut self, lpe: bool) -> Self {
+        self.bits.set_bit(10, lpe);
+        self
+    }
+
+    /// Set store stream read enable.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn with_sre(mut self, sre: bool) -> Self {
+        self.bits.set_bit(11, sre);
+        self
+    }
+
+    /// Set D-cache prefetch lines.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_prefn(mut self, prefn: PrefN) -> Self {
+        self.bits.set_bits(13..=14, prefn as usize);
+        self
+    }
+
+    /// Set L2-cache prefetch enable.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn with_l2pld(mut self, l2pld: bool) -> Self {
+        self.bits.set_bit(15, l2pld);
+        self
+    }
+
+    /// Set L2-cache prefetch distance.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn with_l2_dis(mut self, l2_dis: L2Dis) -> Self {
+        self.bits.set_bits(16..=17, l2_dis as usize);
+        self
+    }
+
+    /// Set L2-cache store prefetch enable.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn with_l2stpld(mut self, l2stpld: bool) -> Self {
+        self.bits.set_bit(18, l2stpld);
+        self
+    }
+
+    /// Set accure exception enable.
+    #[cfg(any(xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_aee(mut self, aee: bool) -> Self {
+        self.bits.set_bit(20, aee);
+        self
+    }
+
+    /// Set TLB maintenance broadcast disable.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    #[inline]
+    pub fn with_tlb_broad_dis(mut self, tlb_broad_dis: bool) -> Self {
+        self.bits.set_bit(21, tlb_broad_dis);
+        self
+    }
+
+    /// Set speculative memory access disable.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn with_no_spec(mut self, no_spec: bool) -> Self {
+        self.bits.set_bit(22, no_spec);
+        self
+    }
+
+    /// Set L1-cache error correction enable.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn with_ecc(mut self, ecc: bool) -> Self {
+        self.bits.set_bit(23, ecc);
+        self
+    }
+
+    /// Set error correction write back disable.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn with_corr_dis(mut self, corr_dis: bool) -> Self {
+        self.bits.set_bit(24, corr_dis);
+        self
+    }
+
+    /// Set TLB prefetch enable.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn with_tlbpld(mut self, tlbpld: bool) -> Self {
+        self.bits.set_bit(25, tlbpld);
+        self
+    }
+
+    /// Set debug PC FIFO freeze.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn with_pcfifo_freeze(mut self, pcfifo_freeze: bool) -> Self {
+        self.bits.set_bit(26, pcfifo_freeze);
+        self
+    }
+
+    /// Set `wrs.nto` and `wrs.sto` wait disable.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn with_wrs_dis(mut self, wrs_dis: bool) -> Self {
+        self.bits.set_bit(27, wrs_dis);
+        self
+    }
+
+    /// Set cache block coherence flush.
+    #[cfg(xuantie_c920)]
+    #[inline]
+    pub fn with_cbcf(mut self, cbcf: bool) -> Self {
+        self.bits.set_bit(28, cbcf);
+        self
+    }
+}
+
+read_csr_as!(Mhint, 0x7C5);
+write_csr_as!(Mhint, 0x7C5);
+
+/// Reads the whole 64-bit register, including `mhint2h` (0x7CA) on RV32
+#[inline]
+pub fn read64() -> u64 {
+    csr::read64::<0x7C5, 0x7CA>()
+}
+
+/// Writes the whole 64-bit register, including `mhint2h` (0x7CA) on RV32
+///
+/// # Safety
+///
+/// Same as [`write`](fn@write); on RV32 both halves are written.
+#[inline]
+pub unsafe fn write64(bits: u64) {
+    csr::write64::<0x7C5, 0x7CA>(bits)
+}
+
 set_clear_csr! {
     /// D-cache prefetch enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     , 0x7C5, set_dpld, clear_dpld, 1 << 2
 }
 set_clear_csr! {
     /// I-cache prefetch enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     , 0x7C5, set_ipld, clear_ipld, 1 << 8
 }
 set_clear_csr! {
@@ -83,26 +557,167 @@ set_clear_csr! {
     /// # Platform support
     ///
     /// This bit is supported on Xuantie E907 and E906 cores.
+    #[cfg(any(xuantie_e907, xuantie_e906))]
     , 0x7C5, set_aee, clear_aee, 1 << 20
 }
-
-// TODO AMR, AMR2, LPE, IWPE, SRE, D_DIS, L2PLD, L2_DIS, NO_SPEC, ECC, 
-// L2STPLD, TLB_BROAD_DIS, CORR_DIS, TLBPLD, PCFIFO_FREEZE, WRS_DIS, CBCF
+set_clear_csr! {
+    /// I-cache way prediction enable
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920, C910 and C906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906))]
+    , 0x7C5, set_iwpe, clear_iwpe, 1 << 9
+}
+set_clear_csr! {
+    /// Loop acceleration enable
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    , 0x7C5, set_lpe, clear_lpe, 1 << 10
+}
+set_clear_csr! {
+    /// Store stream read enable
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    , 0x7C5, set_sre, clear_sre, 1 << 11
+}
+set_clear_csr! {
+    /// L2-cache prefetch enable
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    , 0x7C5, set_l2pld, clear_l2pld, 1 << 15
+}
+set_clear_csr! {
+    /// L2-cache store prefetch enable
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    , 0x7C5, set_l2stpld, clear_l2stpld, 1 << 18
+}
+set_clear_csr! {
+    /// TLB maintenance broadcast disable
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 and C910 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910))]
+    , 0x7C5, set_tlb_broad_dis, clear_tlb_broad_dis, 1 << 21
+}
+set_clear_csr! {
+    /// Speculative memory access disable
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    , 0x7C5, set_no_spec, clear_no_spec, 1 << 22
+}
+set_clear_csr! {
+    /// L1-cache error correction enable
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    , 0x7C5, set_ecc, clear_ecc, 1 << 23
+}
+set_clear_csr! {
+    /// Error correction write back disable
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    , 0x7C5, set_corr_dis, clear_corr_dis, 1 << 24
+}
+set_clear_csr! {
+    /// TLB prefetch enable
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    , 0x7C5, set_tlbpld, clear_tlbpld, 1 << 25
+}
+set_clear_csr! {
+    /// Debug PC FIFO freeze
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    , 0x7C5, set_pcfifo_freeze, clear_pcfifo_freeze, 1 << 26
+}
+set_clear_csr! {
+    /// `wrs.nto` and `wrs.sto` wait disable
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    , 0x7C5, set_wrs_dis, clear_wrs_dis, 1 << 27
+}
+set_clear_csr! {
+    /// Cache block coherence flush
+    ///
+    /// # Platform support
+    ///
+    /// This bit is supported on Xuantie C920 core.
+    #[cfg(xuantie_c920)]
+    , 0x7C5, set_cbcf, clear_cbcf, 1 << 28
+}
 
 /// Set D-cache write allocation strategy
+///
+/// # Safety
+///
+/// Must run in M mode.
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
 #[inline]
 pub unsafe fn set_amr(amr: AMR) {
-    let mut value: usize;
-    asm!("csrr {}, 0x7C5", out(reg) value);
-    value.set_bits(3..=4, amr as usize);
-    asm!("csrw 0x7C5, {}", in(reg) value);
+    csr::write_field::<0x7C5>(0b11 << 3, (amr as usize) << 3);
 }
 
 /// Set D-cache prefetch lines configuration
+///
+/// # Safety
+///
+/// Must run in M mode.
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
 #[inline]
 pub unsafe fn set_prefn(prefn: PrefN) {
-    let mut value: usize;
-    asm!("csrr {}, 0x7C5", out(reg) value);
-    value.set_bits(13..=14, prefn as usize);
-    asm!("csrw 0x7C5, {}", in(reg) value);
+    csr::write_field::<0x7C5>(0b11 << 13, (prefn as usize) << 13);
+}
+
+/// Set L2-cache write allocation strategy
+///
+/// # Safety
+///
+/// Must run in M mode.
+#[cfg(any(xuantie_c920, xuantie_c910))]
+#[inline]
+pub unsafe fn set_amr2(amr2: AMR) {
+    csr::write_field::<0x7C5>(0b11 << 5, (amr2 as usize) << 5);
+}
+
+/// Set L2-cache prefetch distance
+///
+/// # Safety
+///
+/// Must run in M mode.
+#[cfg(any(xuantie_c920, xuantie_c910))]
+#[inline]
+pub unsafe fn set_l2_dis(l2_dis: L2Dis) {
+    csr::write_field::<0x7C5>(0b11 << 16, (l2_dis as usize) << 16);
 }
@@ -1,51 +1,55 @@
 //! mhpmcounter, XuanTie performance counter
+//!
+//! Counters are read as 64-bit values. On RV32 the high half is read from `mhpmcounter{n}h`
+//! with a high-low-high loop, so a carry between the two halves never tears the result.
+use crate::csr;
 
 /// mhpmcounter3: L1 I-cache access counter
-pub fn l1_i_cache_access() -> usize {
-    unsafe { get_csr_value!(0xB03) }
+pub fn l1_i_cache_access() -> u64 {
+    csr::read64::<0xB03, 0xB83>()
 }
 /// mhpmcounter4: L1 I-cache miss counter
-pub fn l1_i_cache_miss() -> usize {
-    unsafe { get_csr_value!(0xB04) }
+pub fn l1_i_cache_miss() -> u64 {
+    csr::read64::<0xB04, 0xB84>()
 }
 /// mhpmcounter5: I-uTLB miss counter
-pub fn i_utlb_miss() -> usize {
-    unsafe { get_csr_value!(0xB05) }
+pub fn i_utlb_miss() -> u64 {
+    csr::read64::<0xB05, 0xB85>()
 }
 /// mhpmcounter6: D-uTLB miss counter
-pub fn d_utlb_miss() -> usize {
-    unsafe { get_csr_value!(0xB06) }
+pub fn d_utlb_miss() -> u64 {
+    csr::read64::<0xB06, 0xB86>()
 }
 /// mhpmcounter7: jTLB miss counter
-pub fn jtlb_miss() -> usize {
-    unsafe { get_csr_value!(0xB07) }
+pub fn jtlb_miss() -> u64 {
+    csr::read64::<0xB07, 0xB87>()
 }
 /// mhpmcounter8: Conditional branch mispredict counter
-pub fn conditional_branch_mispredict() -> usize {
-    unsafe { get_csr_value!(0xB08) }
+pub fn conditional_branch_mispredict() -> u64 {
+    csr::read64::<0xB08, 0xB88>()
 }
 /// mhpmcounter9: Conditional branch instruction counter
-pub fn conditional_branch_instruction() -> usize {
-    unsafe { get_csr_value!(0xB09) }
+pub fn conditional_branch_instruction() -> u64 {
+    csr::read64::<0xB09, 0xB89>()
 }
 /// mhpmcounter13: Store instruction counter
-pub fn store_instruction() -> usize {
-    unsafe { get_csr_value!(0xB0D) }
+pub fn store_instruction() -> u64 {
+    csr::read64::<0xB0D, 0xB8D>()
 }
 /// mhpmcounter14: L1 D-cache read access counter
-pub fn l1_d_cache_read_access() -> usize {
-    unsafe { get_csr_value!(0xB0E) }
+pub fn l1_d_cache_read_access() -> u64 {
+    csr::read64::<0xB0E, 0xB8E>()
 }
 /// mhpmcounter15: L1 D-cache read miss counter
-pub fn l1_d_cache_read_miss() -> usize {
-    unsafe { get_csr_value!(0xB0F) }
+pub fn l1_d_cache_read_miss() -> u64 {
+    csr::read64::<0xB0F, 0xB8F>()
 }
 /// mhpmcounter16: L1 D-cache write access counter
-pub fn l1_d_cache_write_access() -> usize {
-    unsafe { get_csr_value!(0xB10) }
+pub fn l1_d_cache_write_access() -> u64 {
+    csr::read64::<0xB10, 0xB90>()
 }
 /// mhpmcounter17: L1 D-cache write miss counter
-pub fn l1_d_cache_write_miss() -> usize {
-    unsafe { get_csr_value!(0xB11) }
+pub fn l1_d_cache_write_miss() -> u64 {
+    csr::read64::<0xB11, 0xB91>()
 }
 // 10..=12, 18..=31: undefined
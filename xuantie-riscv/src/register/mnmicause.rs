@@ -4,6 +4,7 @@
 //!
 //! This register as well as all its bits are supported on Xuantie E907, E906 and E902 cores.
 use bit_field::BitField;
+use super::Reserved;
 
 /// mnmicause register
 #[derive(Clone, Copy, Debug)]
@@ -20,6 +21,16 @@ pub enum MPP {
 }
 
 impl Mnmicause {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
     /// NMI vector exception code in mcause
     #[inline]
     pub fn nmi_vector(&self) -> usize {
@@ -32,12 +43,12 @@ impl Mnmicause {
     }
     /// NMI mstatus previous privilege mode
     #[inline]
-    pub fn nmi_mpp(&self) -> MPP {
+    pub fn nmi_mpp(&self) -> Result<MPP, Reserved> {
         match self.bits.get_bits(28..=29) {
-            0b00 => MPP::User,
-            0b01 => MPP::Supervisor,
-            0b11 => MPP::Machine,
-            _ => unreachable!(),
+            0b00 => Ok(MPP::User),
+            0b01 => Ok(MPP::Supervisor),
+            0b11 => Ok(MPP::Machine),
+            raw => Err(Reserved(raw)),
         }
     }
     /// NMI INTR (is interrupt) value bit in mcause
@@ -45,6 +56,31 @@ impl Mnmicause {
     pub fn nmi_intr(&self) -> bool {
         self.bits.get_bit(31)
     }
+    /// Set NMI vector exception code in mcause
+    #[inline]
+    pub fn with_nmi_vector(mut self, nmi_vector: usize) -> Self {
+        self.bits.set_bits(0..=11, nmi_vector);
+        self
+    }
+    /// Set NMI mstatus previous interrupt enable
+    #[inline]
+    pub fn with_nmi_mpie(mut self, nmi_mpie: bool) -> Self {
+        self.bits.set_bit(27, nmi_mpie);
+        self
+    }
+    /// Set NMI mstatus previous privilege mode
+    #[inline]
+    pub fn with_nmi_mpp(mut self, nmi_mpp: MPP) -> Self {
+        self.bits.set_bits(28..=29, nmi_mpp as usize);
+        self
+    }
+    /// Set NMI INTR (is interrupt) value bit in mcause
+    #[inline]
+    pub fn with_nmi_intr(mut self, nmi_intr: bool) -> Self {
+        self.bits.set_bit(31, nmi_intr);
+        self
+    }
 }
 
 read_csr_as!(Mnmicause, 0x7E2);
+write_csr_as!(Mnmicause, 0x7E2);
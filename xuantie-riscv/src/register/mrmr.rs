@@ -6,12 +6,16 @@
 //! and its corresponding functionality no longer exists.
 //! Software can still access this register, but reads return zero and
 //! writes have no effect, without triggering an exception.
-use core::arch::asm;
+use crate::csr;
 
 /// Write to mrmr register to release reset lock for given harts
 ///
 /// Write `0x1` for hart 0, `0x2` for 1, `0x4` for 2, etc.
+///
+/// # Safety
+///
+/// Released harts start running from their reset address, which must hold valid code.
 #[inline]
 pub unsafe fn write(bits: usize) {
-    asm!("csrs 0x7C6, {}", in(reg) bits);
+    csr::set::<0x7C6>(bits);
 }
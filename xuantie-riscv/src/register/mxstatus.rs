@@ -4,6 +4,8 @@
 //!
 //! This register is supported on Xuantie C920, C910, C906, E907, E906 and E902 cores.
 use bit_field::BitField;
+#[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906, xuantie_e902))]
+use super::Reserved;
 
 /// mxstatus register
 #[derive(Clone, Copy, Debug)]
@@ -21,11 +23,24 @@ pub enum PM {
 }
 
 impl Mxstatus {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
+
     /// User mode performance monitor enable.
     ///
     /// # Platform support
     ///
     /// This bit is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     #[inline]
     pub fn pmdu(&self) -> bool {
         self.bits.get_bit(10)
@@ -35,7 +50,7 @@ impl Mxstatus {
     ///
     /// # Platform support
     ///
-    /// This bit is supported on Xuantie C920 and TODO cores.
+    /// This bit is supported on Xuantie C920, C910 and C906 cores.
     #[inline]
     pub fn pmds(&self) -> bool {
         self.bits.get_bit(11)
@@ -46,6 +61,7 @@ impl Mxstatus {
     /// # Platform support
     ///
     /// This bit is supported on Xuantie C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     #[inline]
     pub fn pmdm(&self) -> bool {
         self.bits.get_bit(13)
@@ -62,6 +78,7 @@ impl Mxstatus {
     /// # Platform support
     ///
     /// This bit is supported on Xuantie C920, C910, C906, E907 and E906 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     #[inline]
     pub fn mm(&self) -> bool {
         self.bits.get_bit(15)
@@ -71,7 +88,7 @@ impl Mxstatus {
     ///
     /// # Platform support
     ///
-    /// This bit is supported on Xuantie C920 and TODO cores.
+    /// This bit is supported on Xuantie C920, C910 and C906 cores.
     #[inline]
     pub fn ucme(&self) -> bool {
         self.bits.get_bit(16)
@@ -81,7 +98,8 @@ impl Mxstatus {
     ///
     /// # Platform support
     ///
-    /// This bit is supported on Xuantie C920 and TODO cores.
+    /// This bit is supported on Xuantie C920, C910 and C906 cores. Whether the current core
+    /// implements it is reported by [`Capabilities::clintee`](crate::model::Capabilities::clintee).
     #[inline]
     pub fn clintee(&self) -> bool {
         self.bits.get_bit(17)
@@ -100,6 +118,7 @@ impl Mxstatus {
     }
 
     /// Alias for `xuantieisaee` (T-Head extended instruction set architecture enable).
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908, xuantie_e907, xuantie_e906, xuantie_e902))]
     #[inline]
     pub fn theadisaee(&self) -> bool {
         self.xuantieisaee()
@@ -111,7 +130,8 @@ impl Mxstatus {
     ///
     /// # Platform support
     ///
-    /// This bit is supported on Xuantie C920, C910, C906, E907, E906 and E902 cores.
+    /// This bit is supported on Xuantie C920, C910, C906, C907, C908, E907, E906 and E902 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908, xuantie_e907, xuantie_e906, xuantie_e902))]
     #[inline]
     pub fn xuantieisaee(&self) -> bool {
         self.bits.get_bit(22)
@@ -121,7 +141,8 @@ impl Mxstatus {
     ///
     /// # Platform support
     ///
-    /// This bit is supported on Xuantie C920 and TODO cores.
+    /// This bit is supported on Xuantie C920 cores. Whether the current core implements it is
+    /// reported by [`Capabilities::zkt`](crate::model::Capabilities::zkt).
     #[inline]
     pub fn zkte(&self) -> bool {
         self.bits.get_bit(23)
@@ -141,7 +162,8 @@ impl Mxstatus {
     ///
     /// # Platform support
     ///
-    /// This bit is supported on Xuantie C920 and TODO cores.
+    /// This bit is supported on Xuantie C920 cores. Whether the current core implements it is
+    /// reported by [`Capabilities::copinstee`](crate::model::Capabilities::copinstee).
     #[inline]
     pub fn copinstee(&self) -> bool {
         self.bits.get_bit(24)
@@ -152,21 +174,105 @@ impl Mxstatus {
     /// # Platform support
     ///
     /// This bit is supported on Xuantie C920, C910, C906, E907, E906 and E902 cores.
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906, xuantie_e902))]
     #[inline]
-    pub fn pm(&self) -> PM {
+    pub fn pm(&self) -> Result<PM, Reserved> {
         match self.bits.get_bits(30..=31) {
-            0b00 => PM::User,
-            0b01 => PM::Supervisor,
-            0b11 => PM::Machine,
-            _ => unreachable!(),
+            0b00 => Ok(PM::User),
+            0b01 => Ok(PM::Supervisor),
+            0b11 => Ok(PM::Machine),
+            raw => Err(Reserved(raw)),
         }
     }
+
+    /// Set user mode performance monitor enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_pmdu(mut self, pmdu: bool) -> Self {
+        self.bits.set_bit(10, pmdu);
+        self
+    }
+
+    /// Set supervisor mode performance monitor enable
+    #[inline]
+    pub fn with_pmds(mut self, pmds: bool) -> Self {
+        self.bits.set_bit(11, pmds);
+        self
+    }
+
+    /// Set machine mode performance monitor enable
+    #[cfg(any(xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_pmdm(mut self, pmdm: bool) -> Self {
+        self.bits.set_bit(13, pmdm);
+        self
+    }
+
+    /// Set unaligned access enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
+    #[inline]
+    pub fn with_mm(mut self, mm: bool) -> Self {
+        self.bits.set_bit(15, mm);
+        self
+    }
+
+    /// Set user mode allow extended cache instruction
+    #[inline]
+    pub fn with_ucme(mut self, ucme: bool) -> Self {
+        self.bits.set_bit(16, ucme);
+        self
+    }
+
+    /// Set CLINT supervisor extension enable
+    #[inline]
+    pub fn with_clintee(mut self, clintee: bool) -> Self {
+        self.bits.set_bit(17, clintee);
+        self
+    }
+
+    /// Set hardware refill when TLB item absent enable
+    #[inline]
+    pub fn with_mhrd(mut self, mhrd: bool) -> Self {
+        self.bits.set_bit(18, mhrd);
+        self
+    }
+
+    /// Set extend MMU page table entry address attributes enable
+    #[inline]
+    pub fn with_maee(mut self, maee: bool) -> Self {
+        self.bits.set_bit(21, maee);
+        self
+    }
+
+    /// Set T-Head extended instruction set architecture enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908, xuantie_e907, xuantie_e906, xuantie_e902))]
+    #[inline]
+    pub fn with_theadisaee(mut self, theadisaee: bool) -> Self {
+        self.bits.set_bit(22, theadisaee);
+        self
+    }
+
+    /// Set Zkt extension enable
+    #[inline]
+    pub fn with_zkte(mut self, zkte: bool) -> Self {
+        self.bits.set_bit(23, zkte);
+        self
+    }
+
+    /// Set coprocessor instructions enable
+    #[inline]
+    pub fn with_copinstee(mut self, copinstee: bool) -> Self {
+        self.bits.set_bit(24, copinstee);
+        self
+    }
 }
 
 read_csr_as!(Mxstatus, 0x7C0);
+write_csr_as!(Mxstatus, 0x7C0);
 
 set_clear_csr! {
     /// User mode performance monitor enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     , 0x7C0, set_pmdu, clear_pmdu, 1 << 10
 }
 set_clear_csr! {
@@ -175,10 +281,12 @@ set_clear_csr! {
 }
 set_clear_csr! {
     /// Machine mode performance monitor enable
+    #[cfg(any(xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     , 0x7C0, set_pmdm, clear_pmdm, 1 << 13
 }
 set_clear_csr! {
     /// Unaligned access enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_e907, xuantie_e906))]
     , 0x7C0, set_mm, clear_mm, 1 << 15
 }
 set_clear_csr! {
@@ -199,6 +307,7 @@ set_clear_csr! {
 }
 set_clear_csr! {
     /// T-Head extended instruction set architecture enable
+    #[cfg(any(xuantie_c920, xuantie_c910, xuantie_c906, xuantie_c907, xuantie_c908, xuantie_e907, xuantie_e906, xuantie_e902))]
     , 0x7C0, set_theadisaee, clear_theadisaee, 1 << 22
 }
 set_clear_csr! {
@@ -1,6 +1,8 @@
 //! scer, supervisor L1-cache error control register
 pub use super::mcer::RamId;
 use bit_field::BitField;
+use crate::csr;
+use super::Reserved;
 
 /// scer register
 #[derive(Clone, Copy, Debug)]
@@ -9,6 +11,16 @@ pub struct Scer {
 }
 
 impl Scer {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
     /// Error index
     #[inline]
     pub fn err_index(&self) -> u16 {
@@ -21,15 +33,15 @@ impl Scer {
     }
     /// RAM that the error correction fatal error taken place
     #[inline]
-    pub fn ramid(&self) -> RamId {
+    pub fn ramid(&self) -> Result<RamId, Reserved> {
         match self.bits.get_bits(21..=23) {
-            0 => RamId::ICacheTag,
-            1 => RamId::ICacheData,
-            2 => RamId::DCacheTag,
-            3 => RamId::DCacheData,
-            4 => RamId::JTlbTag,
-            5 => RamId::JTlbData,
-            _ => unreachable!(),
+            0 => Ok(RamId::ICacheTag),
+            1 => Ok(RamId::ICacheData),
+            2 => Ok(RamId::DCacheTag),
+            3 => Ok(RamId::DCacheData),
+            4 => Ok(RamId::JTlbTag),
+            5 => Ok(RamId::JTlbData),
+            raw => Err(Reserved(raw)),
         }
     }
     /// Count of errors that are already fixed
@@ -47,6 +59,59 @@ impl Scer {
     pub fn err_vld(&self) -> bool {
         self.bits.get_bit(31)
     }
+    /// Set error index
+    #[inline]
+    pub fn with_err_index(mut self, err_index: u16) -> Self {
+        self.bits.set_bits(0..=15, err_index as usize);
+        self
+    }
+    /// Set error way
+    #[inline]
+    pub fn with_err_way(mut self, err_way: u8) -> Self {
+        self.bits.set_bits(17..=18, err_way as usize);
+        self
+    }
+    /// Set RAM that the error correction fatal error taken place
+    #[inline]
+    pub fn with_ramid(mut self, ramid: RamId) -> Self {
+        self.bits.set_bits(21..=23, ramid as usize);
+        self
+    }
+    /// Set count of errors that are already fixed
+    #[inline]
+    pub fn with_fix_cnt(mut self, fix_cnt: u8) -> Self {
+        self.bits.set_bits(24..=29, fix_cnt as usize);
+        self
+    }
+    /// Set whether L1-cache error correction error is a fatal error
+    #[inline]
+    pub fn with_err_fatal(mut self, err_fatal: bool) -> Self {
+        self.bits.set_bit(30, err_fatal);
+        self
+    }
+    /// Set error correction information valid
+    #[inline]
+    pub fn with_err_vld(mut self, err_vld: bool) -> Self {
+        self.bits.set_bit(31, err_vld);
+        self
+    }
 }
 
 read_csr_as!(Scer, 0x5C3);
+write_csr_as!(Scer, 0x5C3);
+
+/// Reads the whole 64-bit register, including `scerh` (0x5D3) on RV32
+#[inline]
+pub fn read64() -> u64 {
+    csr::read64::<0x5C3, 0x5D3>()
+}
+
+/// Writes the whole 64-bit register, including `scerh` (0x5D3) on RV32
+///
+/// # Safety
+///
+/// Same as [`write`](fn@write); on RV32 both halves are written.
+#[inline]
+pub unsafe fn write64(bits: u64) {
+    csr::write64::<0x5C3, 0x5D3>(bits)
+}
@@ -0,0 +1,12 @@
+//! scounterinten, supervisor counter overflow interrupt enable register
+//!
+//! Supervisor mode view of [`mcounterinten`](super::mcounterinten).
+//!
+//! # Platform support
+//!
+//! This register is supported on Xuantie C920, C910 and C906 cores.
+
+counter_csr! {
+    /// scounterinten register
+    Scounterinten, 0x5C4
+}
@@ -0,0 +1,12 @@
+//! scounterof, supervisor counter overflow register
+//!
+//! Supervisor mode view of [`mcounterof`](super::mcounterof).
+//!
+//! # Platform support
+//!
+//! This register is supported on Xuantie C920, C910 and C906 cores.
+
+counter_csr! {
+    /// scounterof register
+    Scounterof, 0x5C5
+}
@@ -8,6 +8,16 @@ pub struct Shcr {
 }
 
 impl Shcr {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
     /// I-cache enable
     #[inline]
     pub fn ie(&self) -> bool {
@@ -48,6 +58,55 @@ impl Shcr {
     pub fn wbr(&self) -> bool {
         self.bits.get_bit(8)
     }
+    /// Set I-cache enable
+    #[inline]
+    pub fn with_ie(mut self, ie: bool) -> Self {
+        self.bits.set_bit(0, ie);
+        self
+    }
+    /// Set D-cache enable
+    #[inline]
+    pub fn with_de(mut self, de: bool) -> Self {
+        self.bits.set_bit(1, de);
+        self
+    }
+    /// Set cache write allocate configuration enable
+    #[inline]
+    pub fn with_wa(mut self, wa: bool) -> Self {
+        self.bits.set_bit(2, wa);
+        self
+    }
+    /// Set write back enable; true for write back, false for write through
+    #[inline]
+    pub fn with_wb(mut self, wb: bool) -> Self {
+        self.bits.set_bit(3, wb);
+        self
+    }
+    /// Set return stack enable
+    #[inline]
+    pub fn with_rs(mut self, rs: bool) -> Self {
+        self.bits.set_bit(4, rs);
+        self
+    }
+    /// Set branch predict enable
+    #[inline]
+    pub fn with_bpe(mut self, bpe: bool) -> Self {
+        self.bits.set_bit(5, bpe);
+        self
+    }
+    /// Set branch target buffer enable
+    #[inline]
+    pub fn with_btb(mut self, btb: bool) -> Self {
+        self.bits.set_bit(6, btb);
+        self
+    }
+    /// Set write bulk transfer enable
+    #[inline]
+    pub fn with_wbr(mut self, wbr: bool) -> Self {
+        self.bits.set_bit(8, wbr);
+        self
+    }
 }
 
 read_csr_as!(Shcr, 0x5C1);
+write_csr_as!(Shcr, 0x5C1);
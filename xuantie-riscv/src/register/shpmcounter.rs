@@ -0,0 +1,136 @@
+//! shpmcounter, XuanTie supervisor performance counter
+//!
+//! Supervisor mode mirrors of the [`mhpmcounter`](super::mhpmcounter) counters. Reading
+//! `shpmcounter{n}` raises an illegal instruction exception unless machine mode firmware has
+//! set bit `n` of `mcounteren`, see [`delegate`]. Counters only count in supervisor or user mode
+//! while `mxstatus.pmds` or `mxstatus.pmdu` is set; supervisor code can check these bits through
+//! [`supervisor_counting`] and [`user_counting`].
+//!
+//! User mode reads the standard `hpmcounter{n}` registers, which additionally requires bit `n`
+//! of `scounteren`, see [`delegate_to_user`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xuantie_riscv::register::{mxstatus, shpmcounter};
+//! // in machine mode firmware: allow supervisor profiling of D-cache read misses
+//! unsafe {
+//!     mxstatus::set_pmds();
+//!     shpmcounter::delegate(15);
+//! }
+//! // in supervisor mode
+//! if shpmcounter::supervisor_counting() {
+//!     let misses = shpmcounter::l1_d_cache_read_miss();
+//! }
+//! ```
+//!
+//! # Platform support
+//!
+//! These registers are supported on Xuantie C920, C910 and C906 cores.
+use super::sxstatus;
+use crate::csr;
+
+/// shpmcounter3: L1 I-cache access counter
+pub fn l1_i_cache_access() -> usize {
+    get_csr_value!(0x5E3)
+}
+/// shpmcounter4: L1 I-cache miss counter
+pub fn l1_i_cache_miss() -> usize {
+    get_csr_value!(0x5E4)
+}
+/// shpmcounter5: I-uTLB miss counter
+pub fn i_utlb_miss() -> usize {
+    get_csr_value!(0x5E5)
+}
+/// shpmcounter6: D-uTLB miss counter
+pub fn d_utlb_miss() -> usize {
+    get_csr_value!(0x5E6)
+}
+/// shpmcounter7: jTLB miss counter
+pub fn jtlb_miss() -> usize {
+    get_csr_value!(0x5E7)
+}
+/// shpmcounter8: Conditional branch mispredict counter
+pub fn conditional_branch_mispredict() -> usize {
+    get_csr_value!(0x5E8)
+}
+/// shpmcounter9: Conditional branch instruction counter
+pub fn conditional_branch_instruction() -> usize {
+    get_csr_value!(0x5E9)
+}
+/// shpmcounter13: Store instruction counter
+pub fn store_instruction() -> usize {
+    get_csr_value!(0x5ED)
+}
+/// shpmcounter14: L1 D-cache read access counter
+pub fn l1_d_cache_read_access() -> usize {
+    get_csr_value!(0x5EE)
+}
+/// shpmcounter15: L1 D-cache read miss counter
+pub fn l1_d_cache_read_miss() -> usize {
+    get_csr_value!(0x5EF)
+}
+/// shpmcounter16: L1 D-cache write access counter
+pub fn l1_d_cache_write_access() -> usize {
+    get_csr_value!(0x5F0)
+}
+/// shpmcounter17: L1 D-cache write miss counter
+pub fn l1_d_cache_write_miss() -> usize {
+    get_csr_value!(0x5F1)
+}
+// 10..=12, 18..=31: undefined
+
+/// Do performance counters count in supervisor mode, `sxstatus.pmds`
+#[inline]
+pub fn supervisor_counting() -> bool {
+    sxstatus::read().pmds()
+}
+
+/// Do performance counters count in user mode, `sxstatus.pmdu`
+#[inline]
+pub fn user_counting() -> bool {
+    sxstatus::read().pmdu()
+}
+
+/// Allow supervisor mode to read counter `index` by setting its bit in `mcounteren`
+///
+/// # Panics
+///
+/// Panics if `index` is not in `3..=31`.
+///
+/// # Safety
+///
+/// Must run in M mode.
+#[inline]
+pub unsafe fn delegate(index: usize) {
+    assert!((3..=31).contains(&index));
+    csr::set::<0x306>(1 << index)
+}
+
+/// Allow user mode to read counter `index` by setting its bit in `scounteren`
+///
+/// The counter must also be delegated to supervisor mode with [`delegate`].
+///
+/// # Panics
+///
+/// Panics if `index` is not in `3..=31`.
+///
+/// # Safety
+///
+/// Must run in S mode or above.
+#[inline]
+pub unsafe fn delegate_to_user(index: usize) {
+    assert!((3..=31).contains(&index));
+    csr::set::<0x106>(1 << index)
+}
+
+/// Is counter `index` readable in user mode, according to `scounteren`
+///
+/// # Panics
+///
+/// Panics if `index` is not in `3..=31`.
+#[inline]
+pub fn is_delegated_to_user(index: usize) -> bool {
+    assert!((3..=31).contains(&index));
+    csr::read::<0x106>() & (1 << index) != 0
+}
@@ -1,5 +1,6 @@
 //! smeh, supervisor memory entry high register.
 use bit_field::BitField;
+use super::Reserved;
 
 /// smeh register.
 #[derive(Clone, Copy, Debug)]
@@ -19,6 +20,16 @@ pub enum PageSize {
 }
 
 impl Smeh {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
     /// Get address space number.
     #[inline]
     pub fn asid(&self) -> u16 {
@@ -26,12 +37,12 @@ impl Smeh {
     }
     /// Get page size.
     #[inline]
-    pub fn page_size(&self) -> PageSize {
+    pub fn page_size(&self) -> Result<PageSize, Reserved> {
         match self.bits.get_bits(16..=18) {
-            1 => PageSize::Page4K,
-            2 => PageSize::Page2M,
-            4 => PageSize::Page1G,
-            _ => unreachable!(),
+            1 => Ok(PageSize::Page4K),
+            2 => Ok(PageSize::Page2M),
+            4 => Ok(PageSize::Page1G),
+            raw => Err(Reserved(raw)),
         }
     }
     /// Get virtual page number.
@@ -39,6 +50,25 @@ impl Smeh {
     pub fn vpn(&self) -> usize {
         self.bits.get_bits(19..=45)
     }
+    /// Set address space number.
+    #[inline]
+    pub fn with_asid(mut self, asid: u16) -> Self {
+        self.bits.set_bits(0..=15, asid as usize);
+        self
+    }
+    /// Set page size.
+    #[inline]
+    pub fn with_page_size(mut self, page_size: PageSize) -> Self {
+        self.bits.set_bits(16..=18, page_size as usize);
+        self
+    }
+    /// Set virtual page number.
+    #[inline]
+    pub fn with_vpn(mut self, vpn: usize) -> Self {
+        self.bits.set_bits(19..=45, vpn);
+        self
+    }
 }
 
 read_csr_as!(Smeh, 0x9C2);
+write_csr_as!(Smeh, 0x9C2);
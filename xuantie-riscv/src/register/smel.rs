@@ -1,25 +1,56 @@
 //! smel, supervisor memory entry low register.
+//!
+//! This register holds a 64-bit page table entry, and is only available on 64-bit Xuantie
+//! architectures.
 use crate::paging::Entry;
-use core::arch::asm;
 
 /// Reads the smel register.
-///
-/// The `xuantie-riscv`` crate assumes that the `smel` register is available only on
-/// 64-bit Xuantie architectures. Attempting to read the smel register on non-64-bit
-/// architectures using this function will result in an `unimplemented!` panic.
 #[inline]
 pub fn read() -> Entry {
-    match () {
-        // Only enable smel when 64-bit, as it would contain a page table entry
-        #[cfg(target_pointer_width = "64")]
-        () => {
-            let bits: usize;
-            unsafe { asm!("csrr {}, 0x9C1", out(reg) bits) }
-            Entry::from_bits(bits as u64)
-        }
-        #[cfg(not(target_pointer_width = "64"))]
-        () => {
-            unimplemented!()
-        }
+    Entry::from_bits(crate::csr::read::<0x9C1>() as u64)
+}
+
+/// Writes the smel register.
+///
+/// # Safety
+///
+/// Must run in S mode or above. The entry is written into the TLB by a later `smcir`
+/// operation, so it must describe a valid mapping by then.
+#[inline]
+pub unsafe fn write(entry: Entry) {
+    crate::csr::write::<0x9C1>(entry.bits() as usize)
+}
+
+/// Reads the smel register, applies `f` to its value and writes the result back.
+///
+/// # Safety
+///
+/// Same as [`write`](fn@write).
+#[inline]
+pub unsafe fn modify<F: FnOnce(Entry) -> Entry>(f: F) {
+    write(f(read()))
+}
+
+/// The smel register holds a page table entry.
+impl super::Register for Entry {
+    #[inline]
+    fn from_bits(bits: usize) -> Self {
+        Entry::from_bits(bits as u64)
+    }
+    #[inline]
+    fn bits(&self) -> usize {
+        Entry::bits(self) as usize
+    }
+    #[inline]
+    fn read() -> Self {
+        read()
+    }
+    #[inline]
+    unsafe fn write(value: Self) {
+        write(value)
+    }
+    #[inline]
+    unsafe fn modify<F: FnOnce(Self) -> Self>(f: F) {
+        modify(f)
     }
 }
@@ -1,6 +1,7 @@
 //! sxstatus, supervisor extended state register
 pub use super::mxstatus::PM;
 use bit_field::BitField;
+use super::Reserved;
 
 /// sxstatus register
 #[derive(Clone, Copy, Debug)]
@@ -9,6 +10,16 @@ pub struct Sxstatus {
 }
 
 impl Sxstatus {
+    /// Convert bit representation into register value, keeping all the bits.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self { bits }
+    }
+    /// Returns the raw bits of the register.
+    #[inline]
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
     /// User mode performance monitor enable
     #[inline]
     pub fn pmdu(&self) -> bool {
@@ -61,17 +72,72 @@ impl Sxstatus {
     }
     /// Current privileged mode
     #[inline]
-    pub fn pm(&self) -> PM {
+    pub fn pm(&self) -> Result<PM, Reserved> {
         match self.bits.get_bits(30..=31) {
-            0b00 => PM::User,
-            0b01 => PM::Supervisor,
-            0b11 => PM::Machine,
-            _ => unreachable!(),
+            0b00 => Ok(PM::User),
+            0b01 => Ok(PM::Supervisor),
+            0b11 => Ok(PM::Machine),
+            raw => Err(Reserved(raw)),
         }
     }
+    /// Set user mode performance monitor enable
+    #[inline]
+    pub fn with_pmdu(mut self, pmdu: bool) -> Self {
+        self.bits.set_bit(10, pmdu);
+        self
+    }
+    /// Set supervisor mode performance monitor enable
+    #[inline]
+    pub fn with_pmds(mut self, pmds: bool) -> Self {
+        self.bits.set_bit(11, pmds);
+        self
+    }
+    /// Set machine mode performance monitor enable
+    #[inline]
+    pub fn with_pmdm(mut self, pmdm: bool) -> Self {
+        self.bits.set_bit(12, pmdm);
+        self
+    }
+    /// Set unaligned access enable
+    #[inline]
+    pub fn with_mm(mut self, mm: bool) -> Self {
+        self.bits.set_bit(15, mm);
+        self
+    }
+    /// Set user mode allow extended cache instruction
+    #[inline]
+    pub fn with_ucme(mut self, ucme: bool) -> Self {
+        self.bits.set_bit(16, ucme);
+        self
+    }
+    /// Set CLINT supervisor extension enable
+    #[inline]
+    pub fn with_clintee(mut self, clintee: bool) -> Self {
+        self.bits.set_bit(17, clintee);
+        self
+    }
+    /// Set hardware refill when TLB item absent enable
+    #[inline]
+    pub fn with_mhrd(mut self, mhrd: bool) -> Self {
+        self.bits.set_bit(18, mhrd);
+        self
+    }
+    /// Set extend MMU page table entry address attributes enable
+    #[inline]
+    pub fn with_maee(mut self, maee: bool) -> Self {
+        self.bits.set_bit(21, maee);
+        self
+    }
+    /// Set T-Head extended instruction set architecture enable
+    #[inline]
+    pub fn with_theadisaee(mut self, theadisaee: bool) -> Self {
+        self.bits.set_bit(22, theadisaee);
+        self
+    }
 }
 
 read_csr_as!(Sxstatus, 0x5C0);
+write_csr_as!(Sxstatus, 0x5C0);
 
 set_clear_csr! {
     /// Unaligned access enable
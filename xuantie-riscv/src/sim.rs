@@ -0,0 +1,142 @@
+//! Simulated CSR file for host targets.
+//!
+//! When this crate is built for a target other than RISC-V with the `sim` feature, or for
+//! its own unit tests, every CSR access made by the
+//! [`register`](crate::register) modules reads from or writes to an in-memory CSR file instead
+//! of executing `csrr`/`csrw` instructions. Tests may preload CSR values with [`write`],
+//! call driver code built on top of this crate, and inspect the results with [`read`].
+//!
+//! The CSR file is local to the current thread, so tests run in parallel by `cargo test`
+//! do not observe each other's registers. Every CSR reads as zero on a fresh thread.
+//!
+//! Hardware side effects of CSR writes, such as operation bits that clear themselves once
+//! the operation completes, can be modelled with [`set_write_hook`]. Registers returning
+//! a different value on each read can be modelled with [`set_read_hook`].
+//!
+//! # Example
+//!
+//! ```
+//! use xuantie_riscv::{register::mhcr, sim};
+//! // preload mhcr with I-cache and D-cache enabled
+//! sim::write(0x7C1, 0b11);
+//! assert!(mhcr::read().de());
+//! // code under test disables D-cache
+//! unsafe { mhcr::clear_de() };
+//! assert_eq!(sim::read(0x7C1), 0b01);
+//! ```
+extern crate std;
+
+use core::cell::Cell;
+
+/// Number of addressable CSRs in the 12-bit CSR address space.
+const CSR_COUNT: usize = 4096;
+
+/// Hook modelling hardware side effects of a CSR write.
+pub type WriteHook = fn(usize) -> usize;
+/// Hook modelling a CSR whose value changes as it is read.
+pub type ReadHook = fn(usize) -> usize;
+
+std::thread_local! {
+    static CSR_FILE: [Cell<usize>; CSR_COUNT] = const { [const { Cell::new(0) }; CSR_COUNT] };
+    static WRITE_HOOKS: [Cell<Option<WriteHook>>; CSR_COUNT] =
+        const { [const { Cell::new(None) }; CSR_COUNT] };
+    static READ_HOOKS: [Cell<Option<ReadHook>>; CSR_COUNT] =
+        const { [const { Cell::new(None) }; CSR_COUNT] };
+}
+
+/// Reads the simulated value of CSR `csr`.
+///
+/// # Panics
+///
+/// Panics if `csr` is not a valid 12-bit CSR address.
+#[inline]
+pub fn read(csr: u16) -> usize {
+    CSR_FILE.with(|file| file[csr as usize].get())
+}
+
+/// Overwrites the simulated value of CSR `csr`.
+///
+/// # Panics
+///
+/// Panics if `csr` is not a valid 12-bit CSR address.
+#[inline]
+pub fn write(csr: u16, value: usize) {
+    CSR_FILE.with(|file| file[csr as usize].set(value))
+}
+
+/// Installs or removes the write hook of CSR `csr`.
+///
+/// Whenever code under test writes the CSR, the hook receives the written value and returns
+/// the value the CSR holds afterwards. Values stored with [`write`] bypass the hook.
+///
+/// # Example
+///
+/// ```
+/// use xuantie_riscv::{register::mcor, sim};
+/// // cache operations complete instantly: hardware clears INV and CLR bits
+/// sim::set_write_hook(0x7C2, Some(|value| value & !0b11_0000));
+/// unsafe { mcor::cache(mcor::Cache::DATA, mcor::Operation::INVALIDATE) };
+/// assert_eq!(sim::read(0x7C2), 0b10);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `csr` is not a valid 12-bit CSR address.
+#[inline]
+pub fn set_write_hook(csr: u16, hook: Option<WriteHook>) {
+    WRITE_HOOKS.with(|hooks| hooks[csr as usize].set(hook))
+}
+
+/// Installs or removes the read hook of CSR `csr`.
+///
+/// Whenever code under test reads the CSR, the hook receives its current value and returns
+/// the value read, which the CSR then holds. Values returned by [`read`] bypass the hook.
+///
+/// # Example
+///
+/// ```
+/// use xuantie_riscv::{register::mcpuid, sim};
+/// // mcpuid returns the next index word on each read
+/// sim::set_read_hook(0xFC0, Some(|last| ((last >> 28) + 1) % 7 << 28));
+/// let cpuid = mcpuid::read();
+/// assert_eq!(cpuid.word(6), Some(6 << 28));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `csr` is not a valid 12-bit CSR address.
+#[inline]
+pub fn set_read_hook(csr: u16, hook: Option<ReadHook>) {
+    READ_HOOKS.with(|hooks| hooks[csr as usize].set(hook))
+}
+
+/// Resets every simulated CSR of the current thread to zero and removes all hooks.
+#[inline]
+pub fn reset() {
+    CSR_FILE.with(|file| file.iter().for_each(|csr| csr.set(0)));
+    WRITE_HOOKS.with(|hooks| hooks.iter().for_each(|hook| hook.set(None)));
+    READ_HOOKS.with(|hooks| hooks.iter().for_each(|hook| hook.set(None)))
+}
+
+/// Reads CSR `csr` on behalf of code under test, applying its read hook.
+#[inline]
+pub(crate) fn hart_read(csr: u16) -> usize {
+    match READ_HOOKS.with(|hooks| hooks[csr as usize].get()) {
+        Some(hook) => {
+            let value = hook(read(csr));
+            write(csr, value);
+            value
+        }
+        None => read(csr),
+    }
+}
+
+/// Writes CSR `csr` on behalf of code under test, applying its write hook.
+#[inline]
+pub(crate) fn hart_write(csr: u16, value: usize) {
+    let value = match WRITE_HOOKS.with(|hooks| hooks[csr as usize].get()) {
+        Some(hook) => hook(value),
+        None => value,
+    };
+    write(csr, value)
+}